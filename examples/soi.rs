@@ -1,5 +1,11 @@
-use gen_alg::{genalg::GenAlg, individual::dlx::Individual};
-use std::{env, fs, time::Instant};
+use gen_alg::{
+    cache::FitnessCache,
+    config::GenAlgConfig,
+    genalg::GenAlg,
+    individual::dlx::{Individual, Task, emu, pipeline::PipelineConfig},
+    observer::ProgressPrinter,
+};
+use std::{env, fs, sync::Arc, time::Instant};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -22,14 +28,29 @@ fn main() {
         .and_then(|s| s.parse::<usize>().ok())
         .unwrap_or(50);
 
+    let start_indiv = Individual::new(&code_string).unwrap_or_else(|err| {
+        eprintln!("{}: {}", code_file_path, err);
+        std::process::exit(1);
+    });
+
     let start_timer = Instant::now();
 
-    let mut gen_alg = GenAlg::<Individual>::new(
-        pop_size,
-        Some(&vec![Individual::new(&code_string); pop_size]),
+    let mut gen_alg = GenAlg::<Individual>::new(pop_size, Some(&vec![start_indiv; pop_size]))
+        .with_observer(ProgressPrinter::new().with_interval(10))
+        .with_fitness_cache(Arc::new(FitnessCache::new(10_000)));
+
+    gen_alg.set_config(
+        GenAlgConfig::builder()
+            .with_generations(gen_num)
+            .with_selection_rate(0.5)
+            .with_mutation_rate(0.5)
+            .with_elite_count(5)
+            .build()
+            .unwrap(),
     );
 
-    let best = gen_alg.run_genetic_algorithm(gen_num, 0.5, 0.5, 5).unwrap();
+    let result = gen_alg.run_genetic_algorithm().unwrap();
+    let best = result.best;
 
     let first_best = gen_alg
         .population_history()
@@ -41,10 +62,34 @@ fn main() {
     println!("{}", code_string);
     println!("{}\nFitness: {}", best.obj(), best.fitness());
     println!("Cycle count: {}", 20000 - best.fitness() as i32,);
+    println!(
+        "Stopped after {} generations: {}",
+        result.generations, result.stop_reason
+    );
     println!(
         "Original cycle count: {}",
         20000 - first_best.fitness() as i32,
     );
+    if let Some(cache) = gen_alg.fitness_cache() {
+        let stats = cache.stats();
+        println!(
+            "Fitness cache: {} hits, {} misses ({:.1}% hit rate)",
+            stats.hits,
+            stats.misses,
+            stats.hit_rate() * 100.0
+        );
+    }
+
+    // The GA only sees the reference samples, check that the result is not overfitted to them
+    let random_task = Arc::new(Task::soi_random(10, &mut rand::rng()));
+    match best.obj().clone().with_task(random_task).evaluate() {
+        Ok(evaluation) => println!("Random samples: {}", evaluation),
+        Err(err) => println!("Random samples: {}", err),
+    }
+
+    if let Ok(report) = emu::run_native_pipeline(best.obj(), PipelineConfig::default()) {
+        print!("{}", report);
+    }
 
     //print!("{:?}\n", gen_alg.population_history());
 
@@ -0,0 +1,76 @@
+//! Emulator worker backed by the native DLX emulator.
+//!
+//! Speaks the protocol of `gen_alg::individual::dlx::worker` on stdin/stdout, so `WorkerPool`
+//! can be used without the Python emulator. Requests without memory start from the reference
+//! memory image, programs run on the SOI task with the cycle budget of the request.
+//!
+//! For testing the pool, `--crash-after <n>` exits after answering `n` requests and
+//! `--delay-ms <ms>` waits before every response.
+
+use gen_alg::individual::dlx::{
+    EmulatorBackend, Individual, Task, backend::NativeBackend, emu, emu::EmulatorResult, worker,
+};
+use std::{
+    env,
+    io::{self, BufWriter},
+    process,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+fn parse_flag(args: &[String], flag: &str) -> Option<u64> {
+    let position = args.iter().position(|arg| arg == flag)?;
+
+    match args.get(position + 1).and_then(|value| value.parse().ok()) {
+        Some(value) => Some(value),
+        None => {
+            eprintln!("Usage: {} [--crash-after <n>] [--delay-ms <ms>]", args[0]);
+            process::exit(2);
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let crash_after = parse_flag(&args, "--crash-after");
+    let delay = parse_flag(&args, "--delay-ms").map(Duration::from_millis);
+
+    let backend = NativeBackend::new();
+    let mut stdin = io::stdin().lock();
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    let mut answered = 0;
+
+    while let Some(request) = worker::read_request(&mut stdin)? {
+        if crash_after == Some(answered) {
+            process::exit(1);
+        }
+
+        let memory = if request.memory.is_empty() {
+            emu::reference_memory()
+        } else {
+            request.memory
+        };
+        let task = Arc::new(Task::clone(&Task::soi()).with_cycle_budget(request.cycle_budget));
+        // The native backend cannot fail, programs which do not parse are reported as failed runs
+        let result = Individual::parse(&request.program)
+            .map(|program| program.with_task(task))
+            .map_err(|err| eprintln!("{}", err))
+            .and_then(|program| backend.run(&program, &memory).map_err(|_| ()))
+            .unwrap_or(EmulatorResult {
+                success: false,
+                cycle_count: 0,
+                unknown: false,
+                memory: None,
+            });
+
+        if let Some(delay) = delay {
+            thread::sleep(delay);
+        }
+
+        worker::write_response(&mut stdout, &result)?;
+        answered += 1;
+    }
+
+    Ok(())
+}
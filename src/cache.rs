@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Which entry a full `FitnessCache` drops to make room for a new one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Drops the entry which was used the longest time ago
+    #[default]
+    LeastRecentlyUsed,
+    /// Drops the entry which was inserted first
+    FirstInFirstOut,
+}
+
+/// Hit and miss counts of a `FitnessCache`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+    /// Number of cached entries
+    pub len: usize,
+}
+
+impl CacheStats {
+    /// Fraction of lookups answered from the cache (0.0 - 1.0)
+    pub fn hit_rate(&self) -> f32 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f32 / lookups as f32
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Fitness and last use of every key
+    entries: HashMap<u64, (f32, u64)>,
+    /// Keys ordered by last use, oldest first
+    order: BTreeMap<u64, u64>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+/// Bounded, thread-safe map from the cache key of a genome to its fitness.
+///
+/// Keys come from `Genetic::cache_key`, individuals without a key are never cached.
+#[derive(Debug)]
+pub struct FitnessCache {
+    capacity: usize,
+    policy: EvictionPolicy,
+    inner: Mutex<Inner>,
+}
+
+impl FitnessCache {
+    /// Creates a least recently used cache holding up to `capacity` fitness values
+    pub fn new(capacity: usize) -> Self {
+        FitnessCache {
+            capacity,
+            policy: EvictionPolicy::default(),
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Sets which entry is dropped when the cache is full
+    pub fn with_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Getter for maximum number of entries
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Getter for eviction policy
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    /// Returns the cached fitness of `key` and counts the hit or miss
+    pub fn get(&self, key: u64) -> Option<f32> {
+        let mut inner = self.lock();
+        let inner = &mut *inner;
+
+        let Some((fitness, last_use)) = inner.entries.get_mut(&key) else {
+            inner.stats.misses += 1;
+            return None;
+        };
+
+        if self.policy == EvictionPolicy::LeastRecentlyUsed {
+            inner.order.remove(last_use);
+            inner.clock += 1;
+            *last_use = inner.clock;
+            inner.order.insert(inner.clock, key);
+        }
+
+        inner.stats.hits += 1;
+        Some(*fitness)
+    }
+
+    /// Stores the fitness of `key`, evicting an entry if the cache is full
+    pub fn insert(&self, key: u64, fitness: f32) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        if let Some((_, last_use)) = inner.entries.insert(key, (fitness, clock)) {
+            inner.order.remove(&last_use);
+        } else if inner.entries.len() > self.capacity
+            && let Some((_, oldest)) = inner.order.pop_first()
+        {
+            inner.entries.remove(&oldest);
+            inner.stats.evictions += 1;
+        }
+
+        inner.order.insert(clock, key);
+        inner.stats.len = inner.entries.len();
+    }
+
+    /// Returns the fitness of `key`, computing and storing it on a miss
+    pub fn get_or_insert_with(&self, key: u64, fitness: impl FnOnce() -> f32) -> f32 {
+        if let Some(fitness) = self.get(key) {
+            return fitness;
+        }
+
+        // computed without holding the lock, other threads can use the cache meanwhile
+        let fitness = fitness();
+        self.insert(key, fitness);
+        fitness
+    }
+
+    /// Returns the hit and miss counts
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Drops all entries, keeping the statistics
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.order.clear();
+        inner.stats.len = 0;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hits_and_misses() {
+        let cache = FitnessCache::new(10);
+
+        assert_eq!(cache.get(1), None);
+        cache.insert(1, 5.0);
+        assert_eq!(cache.get(1), Some(5.0));
+        assert_eq!(cache.get_or_insert_with(2, || 7.0), 7.0);
+        assert_eq!(cache.get_or_insert_with(2, || unreachable!()), 7.0);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (2, 2, 2));
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn test_least_recently_used_eviction() {
+        let cache = FitnessCache::new(2);
+
+        cache.insert(1, 1.0);
+        cache.insert(2, 2.0);
+        cache.get(1);
+        cache.insert(3, 3.0);
+
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(1), Some(1.0));
+        assert_eq!(cache.get(3), Some(3.0));
+        assert_eq!(cache.stats().evictions, 1);
+        assert_eq!(cache.stats().len, 2);
+    }
+
+    #[test]
+    fn test_first_in_first_out_eviction() {
+        let cache = FitnessCache::new(2).with_policy(EvictionPolicy::FirstInFirstOut);
+
+        cache.insert(1, 1.0);
+        cache.insert(2, 2.0);
+        cache.get(1);
+        cache.insert(3, 3.0);
+
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.get(2), Some(2.0));
+    }
+
+    #[test]
+    fn test_reinsert_and_zero_capacity() {
+        let cache = FitnessCache::new(2);
+        cache.insert(1, 1.0);
+        cache.insert(1, 4.0);
+        assert_eq!(cache.get(1), Some(4.0));
+        assert_eq!(cache.stats().len, 1);
+
+        let cache = FitnessCache::new(0);
+        cache.insert(1, 1.0);
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.stats().len, 0);
+    }
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rand_chacha::ChaCha8Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GenAlgConfig;
+use crate::error::CheckpointError;
+use crate::genalg::{FitnessIndiv, GenAlg, RunProgress};
+use crate::individual::genetic::Genetic;
+use crate::stats::GenerationStats;
+
+/// State of a `GenAlg` from which a run continues exactly as if it was never interrupted.
+///
+/// Observers, stop conditions, the selection strategy, the fitness cache and the population
+/// history are not saved, they are set again on the resumed `GenAlg`. Individuals keep only
+/// what they serialize, `GenAlg::from_checkpoint` restores the rest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    pub(crate) config: GenAlgConfig,
+    pub(crate) population: Vec<FitnessIndiv<T>>,
+    pub(crate) best: Option<FitnessIndiv<T>>,
+    pub(crate) generation: usize,
+    pub(crate) evaluations: usize,
+    pub(crate) evaluation_errors: usize,
+    pub(crate) cache_hits: usize,
+    pub(crate) rng: ChaCha8Rng,
+    pub(crate) generation_stats: Vec<GenerationStats>,
+    /// Progress of the run the checkpoint was saved in, if any
+    pub(crate) run: Option<RunProgress>,
+}
+
+impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> Checkpoint<T> {
+    /// Getter for configuration
+    pub fn config(&self) -> &GenAlgConfig {
+        &self.config
+    }
+
+    /// Getter for population, with the fitness of every individual
+    pub fn population(&self) -> &[FitnessIndiv<T>] {
+        &self.population
+    }
+
+    /// Getter for best individual found before the checkpoint
+    pub fn best(&self) -> Option<&FitnessIndiv<T>> {
+        self.best.as_ref()
+    }
+
+    /// Getter for number of generations completed by the `GenAlg`
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Writes the checkpoint as JSON to `path`.
+    ///
+    /// The file is written next to `path` and renamed over it, so an interrupted save
+    /// keeps the previous checkpoint.
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError>
+    where
+        T: Serialize,
+    {
+        let json = serde_json::to_vec(self)?;
+
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+        fs::write(&partial, json)?;
+        fs::rename(&partial, path)?;
+
+        Ok(())
+    }
+
+    /// Reads a checkpoint written by `save`
+    pub fn load(path: &Path) -> Result<Self, CheckpointError>
+    where
+        T: DeserializeOwned,
+    {
+        let json = fs::read(path)?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
+
+/// Saves a `GenAlg` to a path, captured where `T` is known to be serializable
+pub(crate) type SaveFn<T> =
+    fn(&GenAlg<T>, Option<RunProgress>, &Path) -> Result<(), CheckpointError>;
+
+/// Where and how often a `GenAlg` saves checkpoints
+pub(crate) struct CheckpointSchedule<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    pub path: PathBuf,
+    pub interval: usize,
+    pub save: SaveFn<T>,
+}
@@ -0,0 +1,553 @@
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// What `GenAlg` keeps of past generations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HistoryRetention {
+    /// Keeps neither populations nor generation statistics
+    None,
+    /// Keeps the populations of the last generations and all statistics
+    LastGenerations(usize),
+    /// Keeps the best individual of every generation, as a population of one, and all statistics
+    BestOnly,
+    /// Keeps only generation statistics
+    StatsOnly,
+    /// Keeps every population and all statistics
+    #[default]
+    Full,
+}
+
+impl HistoryRetention {
+    /// Whether generation statistics are kept
+    pub fn keeps_stats(&self) -> bool {
+        *self != HistoryRetention::None
+    }
+}
+
+/// Individual replaced by a child in steady-state generations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SteadyStateReplacement {
+    /// The worst individual of the population
+    #[default]
+    Worst,
+    /// The worst of this many randomly chosen individuals
+    TournamentLoser(usize),
+}
+
+/// How `GenAlg` produces the next population
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EvolutionMode {
+    /// Every generation keeps the selected individuals and replaces the rest by children
+    #[default]
+    Generational,
+    /// Every generation creates `offspring` children, each replacing one individual.
+    ///
+    /// Parents come from the selected individuals like in generational mode,
+    /// elite individuals are never replaced.
+    SteadyState {
+        offspring: usize,
+        replacement: SteadyStateReplacement,
+    },
+}
+
+/// Parameters of a genetic algorithm run.
+///
+/// Built and validated with `GenAlgConfig::builder()`, the default configuration is always valid.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GenAlgConfig {
+    generations: usize,
+    selection_rate: f32,
+    mutation_rate: f32,
+    crossover_rate: f32,
+    elite_count: usize,
+    parent_count: usize,
+    history: HistoryRetention,
+    threads: Option<usize>,
+    seed: Option<u64>,
+    mode: EvolutionMode,
+}
+
+impl Default for GenAlgConfig {
+    fn default() -> Self {
+        GenAlgConfig {
+            generations: 100,
+            selection_rate: 0.5,
+            mutation_rate: 0.05,
+            crossover_rate: 1.0,
+            elite_count: 0,
+            parent_count: 2,
+            history: HistoryRetention::Full,
+            threads: None,
+            seed: None,
+            mode: EvolutionMode::Generational,
+        }
+    }
+}
+
+impl GenAlgConfig {
+    /// Starts building a configuration from the default one
+    pub fn builder() -> GenAlgConfigBuilder {
+        GenAlgConfigBuilder {
+            config: GenAlgConfig::default(),
+        }
+    }
+
+    /// Getter for number of generations
+    pub fn generations(&self) -> usize {
+        self.generations
+    }
+
+    /// Getter for fraction of population selected for crossover
+    pub fn selection_rate(&self) -> f32 {
+        self.selection_rate
+    }
+
+    /// Getter for fraction of population mutated
+    pub fn mutation_rate(&self) -> f32 {
+        self.mutation_rate
+    }
+
+    /// Getter for fraction of children made by crossover, the rest are copies of a parent
+    pub fn crossover_rate(&self) -> f32 {
+        self.crossover_rate
+    }
+
+    /// Getter for number of elite individuals
+    pub fn elite_count(&self) -> usize {
+        self.elite_count
+    }
+
+    /// Getter for number of parents of every child
+    pub fn parent_count(&self) -> usize {
+        self.parent_count
+    }
+
+    /// Getter for what is kept of past generations
+    pub fn history(&self) -> HistoryRetention {
+        self.history
+    }
+
+    /// Getter for number of threads, `None` uses the global rayon thread pool
+    pub fn threads(&self) -> Option<usize> {
+        self.threads
+    }
+
+    /// Getter for random seed, `None` seeds every run from entropy
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Getter for how the next population is produced
+    pub fn mode(&self) -> EvolutionMode {
+        self.mode
+    }
+
+    /// Copy of the configuration with another seed
+    pub(crate) fn reseeded(&self, seed: u64) -> GenAlgConfig {
+        GenAlgConfig {
+            seed: Some(seed),
+            ..self.clone()
+        }
+    }
+
+    /// Number of individuals selected for crossover in a population of `population_size`
+    pub fn selected_count(&self, population_size: usize) -> usize {
+        (population_size as f32 * self.selection_rate).floor() as usize
+    }
+
+    /// Checks the parameters which do not depend on the population
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (name, value) in [
+            ("selection_rate", self.selection_rate),
+            ("mutation_rate", self.mutation_rate),
+            ("crossover_rate", self.crossover_rate),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(ConfigError::RateOutOfRange { name, value });
+            }
+        }
+
+        if self.parent_count < 2 {
+            return Err(ConfigError::TooFewParents(self.parent_count));
+        }
+
+        if self.threads == Some(0) {
+            return Err(ConfigError::ZeroThreads);
+        }
+
+        if let EvolutionMode::SteadyState {
+            offspring,
+            replacement,
+        } = self.mode
+        {
+            if offspring == 0 {
+                return Err(ConfigError::NoOffspring);
+            }
+            if replacement == SteadyStateReplacement::TournamentLoser(0) {
+                return Err(ConfigError::EmptyTournament);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks all parameters for a population of `population_size`
+    pub fn validate_for_population(&self, population_size: usize) -> Result<(), ConfigError> {
+        self.validate()?;
+
+        if self.elite_count > population_size {
+            return Err(ConfigError::EliteCountTooLarge {
+                elite_count: self.elite_count,
+                population_size,
+            });
+        }
+
+        if let EvolutionMode::SteadyState { offspring, .. } = self.mode
+            && offspring > population_size - self.elite_count
+        {
+            return Err(ConfigError::TooManyOffspring {
+                offspring,
+                replaceable: population_size - self.elite_count,
+            });
+        }
+
+        let selected_count = self.selected_count(population_size);
+        if selected_count < self.parent_count {
+            return Err(ConfigError::TooFewSelected {
+                selected_count,
+                parent_count: self.parent_count,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for `GenAlgConfig`
+#[derive(Debug, Clone)]
+pub struct GenAlgConfigBuilder {
+    config: GenAlgConfig,
+}
+
+impl GenAlgConfigBuilder {
+    /// Sets the number of generations to run
+    pub fn with_generations(mut self, generations: usize) -> Self {
+        self.config.generations = generations;
+        self
+    }
+
+    /// Sets the fraction of population to select for crossover (0.0 - 1.0)
+    pub fn with_selection_rate(mut self, selection_rate: f32) -> Self {
+        self.config.selection_rate = selection_rate;
+        self
+    }
+
+    /// Sets the fraction of population to mutate (0.0 - 1.0)
+    pub fn with_mutation_rate(mut self, mutation_rate: f32) -> Self {
+        self.config.mutation_rate = mutation_rate;
+        self
+    }
+
+    /// Sets the fraction of children made by crossover (0.0 - 1.0)
+    pub fn with_crossover_rate(mut self, crossover_rate: f32) -> Self {
+        self.config.crossover_rate = crossover_rate;
+        self
+    }
+
+    /// Sets the number of elite individuals kept unchanged in the population
+    pub fn with_elite_count(mut self, elite_count: usize) -> Self {
+        self.config.elite_count = elite_count;
+        self
+    }
+
+    /// Sets the number of parents crossed over into every child, at least 2
+    pub fn with_parent_count(mut self, parent_count: usize) -> Self {
+        self.config.parent_count = parent_count;
+        self
+    }
+
+    /// Sets what is kept of past generations, every population by default
+    pub fn with_history(mut self, history: HistoryRetention) -> Self {
+        self.config.history = history;
+        self
+    }
+
+    /// Sets the number of threads used for evaluation
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.config.threads = Some(threads);
+        self
+    }
+
+    /// Sets the seed making runs reproducible, regardless of the number of threads
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.config.seed = Some(seed);
+        self
+    }
+
+    /// Sets how the next population is produced, generational by default
+    pub fn with_mode(mut self, mode: EvolutionMode) -> Self {
+        self.config.mode = mode;
+        self
+    }
+
+    /// Validates and returns the configuration
+    pub fn build(self) -> Result<GenAlgConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Invalid genetic algorithm configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// Rate parameter outside of [0.0, 1.0]
+    RateOutOfRange { name: &'static str, value: f32 },
+    /// Less than 2 parents per child
+    TooFewParents(usize),
+    /// Thread pool with no threads
+    ZeroThreads,
+    /// More elite individuals than the population has
+    EliteCountTooLarge {
+        elite_count: usize,
+        population_size: usize,
+    },
+    /// Selection rate selects fewer individuals than a child needs parents
+    TooFewSelected {
+        selected_count: usize,
+        parent_count: usize,
+    },
+    /// Thread pool with the configured number of threads could not be built
+    ThreadPool(String),
+    /// Steady-state generations without children
+    NoOffspring,
+    /// Steady-state replacement tournament of size 0
+    EmptyTournament,
+    /// More steady-state children than individuals which are not elite
+    TooManyOffspring {
+        offspring: usize,
+        replaceable: usize,
+    },
+    /// Island model without islands
+    NoIslands,
+    /// Migration every 0 generations
+    ZeroMigrationInterval,
+    /// Checkpoint every 0 generations
+    ZeroCheckpointInterval,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::RateOutOfRange { name, value } => {
+                write!(f, "{} must be in [0.0, 1.0], got {}", name, value)
+            }
+            ConfigError::TooFewParents(count) => {
+                write!(f, "parent_count must be at least 2, got {}", count)
+            }
+            ConfigError::ZeroThreads => write!(f, "threads must be at least 1"),
+            ConfigError::EliteCountTooLarge {
+                elite_count,
+                population_size,
+            } => write!(
+                f,
+                "elite_count {} cannot be greater than population size {}",
+                elite_count, population_size
+            ),
+            ConfigError::TooFewSelected {
+                selected_count,
+                parent_count,
+            } => write!(
+                f,
+                "selection_rate too small, it selects {} individuals but every child needs {} parents",
+                selected_count, parent_count
+            ),
+            ConfigError::ThreadPool(message) => {
+                write!(f, "thread pool could not be built: {}", message)
+            }
+            ConfigError::NoOffspring => write!(f, "steady-state offspring must be at least 1"),
+            ConfigError::EmptyTournament => {
+                write!(f, "replacement tournament size must be at least 1")
+            }
+            ConfigError::TooManyOffspring {
+                offspring,
+                replaceable,
+            } => write!(
+                f,
+                "steady-state offspring {} cannot be greater than the {} individuals which are not elite",
+                offspring, replaceable
+            ),
+            ConfigError::NoIslands => write!(f, "island model needs at least 1 island"),
+            ConfigError::ZeroMigrationInterval => {
+                write!(f, "migration interval must be at least 1")
+            }
+            ConfigError::ZeroCheckpointInterval => {
+                write!(f, "checkpoint interval must be at least 1")
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert_eq!(GenAlgConfig::default().validate(), Ok(()));
+        assert_eq!(GenAlgConfig::builder().build(), Ok(GenAlgConfig::default()));
+    }
+
+    #[test]
+    fn test_builder() {
+        let config = GenAlgConfig::builder()
+            .with_generations(10)
+            .with_selection_rate(0.3)
+            .with_mutation_rate(0.2)
+            .with_crossover_rate(0.9)
+            .with_elite_count(4)
+            .with_parent_count(3)
+            .with_history(HistoryRetention::LastGenerations(3))
+            .with_threads(2)
+            .with_seed(7)
+            .with_mode(EvolutionMode::SteadyState {
+                offspring: 2,
+                replacement: SteadyStateReplacement::TournamentLoser(3),
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(config.generations(), 10);
+        assert_eq!(config.selection_rate(), 0.3);
+        assert_eq!(config.mutation_rate(), 0.2);
+        assert_eq!(config.crossover_rate(), 0.9);
+        assert_eq!(config.elite_count(), 4);
+        assert_eq!(config.parent_count(), 3);
+        assert_eq!(config.history(), HistoryRetention::LastGenerations(3));
+        assert_eq!(config.threads(), Some(2));
+        assert_eq!(config.seed(), Some(7));
+        assert_eq!(
+            config.mode(),
+            EvolutionMode::SteadyState {
+                offspring: 2,
+                replacement: SteadyStateReplacement::TournamentLoser(3)
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert_eq!(
+            GenAlgConfig::builder().with_selection_rate(2.0).build(),
+            Err(ConfigError::RateOutOfRange {
+                name: "selection_rate",
+                value: 2.0
+            })
+        );
+        assert!(matches!(
+            GenAlgConfig::builder().with_mutation_rate(-0.1).build(),
+            Err(ConfigError::RateOutOfRange {
+                name: "mutation_rate",
+                ..
+            })
+        ));
+        assert!(matches!(
+            GenAlgConfig::builder()
+                .with_crossover_rate(f32::NAN)
+                .build(),
+            Err(ConfigError::RateOutOfRange {
+                name: "crossover_rate",
+                ..
+            })
+        ));
+        assert_eq!(
+            GenAlgConfig::builder().with_parent_count(1).build(),
+            Err(ConfigError::TooFewParents(1))
+        );
+        assert_eq!(
+            GenAlgConfig::builder().with_threads(0).build(),
+            Err(ConfigError::ZeroThreads)
+        );
+        assert_eq!(
+            GenAlgConfig::builder()
+                .with_mode(EvolutionMode::SteadyState {
+                    offspring: 0,
+                    replacement: SteadyStateReplacement::Worst
+                })
+                .build(),
+            Err(ConfigError::NoOffspring)
+        );
+        assert_eq!(
+            GenAlgConfig::builder()
+                .with_mode(EvolutionMode::SteadyState {
+                    offspring: 1,
+                    replacement: SteadyStateReplacement::TournamentLoser(0)
+                })
+                .build(),
+            Err(ConfigError::EmptyTournament)
+        );
+    }
+
+    #[test]
+    fn test_validate_for_population() {
+        let config = GenAlgConfig::builder()
+            .with_selection_rate(0.1)
+            .with_elite_count(5)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.validate_for_population(100), Ok(()));
+        assert_eq!(
+            config.validate_for_population(4),
+            Err(ConfigError::EliteCountTooLarge {
+                elite_count: 5,
+                population_size: 4
+            })
+        );
+        assert_eq!(
+            config.validate_for_population(10),
+            Err(ConfigError::TooFewSelected {
+                selected_count: 1,
+                parent_count: 2
+            })
+        );
+
+        let steady_state = GenAlgConfig::builder()
+            .with_elite_count(5)
+            .with_mode(EvolutionMode::SteadyState {
+                offspring: 10,
+                replacement: SteadyStateReplacement::Worst,
+            })
+            .build()
+            .unwrap();
+        assert_eq!(steady_state.validate_for_population(15), Ok(()));
+        assert_eq!(
+            steady_state.validate_for_population(12),
+            Err(ConfigError::TooManyOffspring {
+                offspring: 10,
+                replaceable: 7
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_serde() {
+        let config = GenAlgConfig::builder()
+            .with_generations(42)
+            .with_threads(3)
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<GenAlgConfig>(&json).unwrap(), config);
+    }
+}
@@ -0,0 +1,118 @@
+"""Emulator worker backed by the Python reference emulator.
+
+Speaks the protocol of `gen_alg::individual::dlx::worker` on stdin/stdout, like the
+native `emu_worker` binary, but runs every program through `interface.py` from the
+`src/emulator` submodule. The interpreter and the emulator modules stay loaded
+between requests, which saves starting a process per evaluation.
+
+The reference emulator loads its own memory image. Requests starting from any
+other image are answered with an error, requests without memory run as usual.
+The cycle budget of each request is passed to the emulator as its timeout.
+
+Usage: python3 -B -u src/emu_worker.py [--emulator-dir <dir>]
+"""
+
+import argparse
+import contextlib
+import io
+import os
+import runpy
+import sys
+
+DEFAULT_EMULATOR_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "emulator")
+
+# Memory image of the reference emulator, as built by `emu::reference_memory`
+MEMORY_SIZE = 256
+SOI_INPUT_ADDR = 0x200 // 4
+SOI_INPUT_SIZE = 32
+SOI_COEFFICIENTS_ADDR = 0x2C0 // 4
+SOI_COEFFICIENTS_SIZE = 16
+
+
+def reference_memory():
+    memory = [0] * MEMORY_SIZE
+    memory[SOI_INPUT_ADDR:SOI_INPUT_ADDR + SOI_INPUT_SIZE] = range(1, SOI_INPUT_SIZE + 1)
+    memory[SOI_COEFFICIENTS_ADDR:SOI_COEFFICIENTS_ADDR + SOI_COEFFICIENTS_SIZE] = [1] * SOI_COEFFICIENTS_SIZE
+    return memory
+
+
+def read_request(stdin):
+    """Reads a request and returns its program, memory and cycle budget, or None once the input is closed."""
+    header = stdin.readline()
+    if not header:
+        return None
+
+    parts = header.split()
+    if len(parts) != 4 or parts[0] != "RUN":
+        raise ValueError(f"invalid request header '{header.strip()}'")
+
+    line_count, word_count, cycle_budget = int(parts[1]), int(parts[2]), int(parts[3])
+    program = "".join(stdin.readline() for _ in range(line_count))
+    memory = [int(word, 16) for word in stdin.readline().split()] if word_count > 0 else []
+
+    return program, memory, cycle_budget
+
+
+def is_reference_memory(memory):
+    """Checks that the memory, padded with zeros like the native machine does, is the reference image."""
+    if len(memory) > MEMORY_SIZE:
+        return False
+    return memory + [0] * (MEMORY_SIZE - len(memory)) == reference_memory()
+
+
+def run_program(program, timeout):
+    """Runs interface.py on the program and returns what it printed.
+
+    Programs the emulator raises on are reported as failed runs, the error goes to stderr.
+    """
+    output = io.StringIO()
+    argv = sys.argv
+    sys.argv = ["interface.py", "--instr", program, "--timeout", str(timeout)]
+
+    try:
+        with contextlib.redirect_stdout(output):
+            runpy.run_path("interface.py", run_name="__main__")
+    except SystemExit as exit:
+        if exit.code not in (None, 0):
+            return "Failed"
+    except Exception as err:
+        print(f"emulator error: {err}", file=sys.stderr)
+        return "Failed"
+    finally:
+        sys.argv = argv
+
+    return output.getvalue().strip()
+
+
+def write_response(stdout, output):
+    lines = output.splitlines()
+    stdout.write(f"RESULT {len(lines)}\n")
+    for line in lines:
+        stdout.write(f"{line}\n")
+    stdout.flush()
+
+
+def write_error(stdout, message):
+    stdout.write(f"ERROR {message}\n")
+    stdout.flush()
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--emulator-dir", default=DEFAULT_EMULATOR_DIR)
+    args = parser.parse_args()
+
+    # interface.py imports its modules and reads its files relative to its own directory
+    os.chdir(args.emulator_dir)
+    sys.path.insert(0, os.getcwd())
+
+    while (request := read_request(sys.stdin)) is not None:
+        program, memory, cycle_budget = request
+        if memory and not is_reference_memory(memory):
+            write_error(sys.stdout, "the reference emulator only starts from its own memory image")
+        else:
+            write_response(sys.stdout, run_program(program, cycle_budget))
+
+
+if __name__ == "__main__":
+    main()
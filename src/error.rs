@@ -0,0 +1,283 @@
+use std::fmt;
+use std::io;
+
+use crate::config::ConfigError;
+use crate::individual::dlx::task::{ParseTaskError, RegionError};
+
+/// Errors of the genetic algorithm and the DLX individuals
+#[derive(Debug)]
+pub enum Error {
+    /// Invalid genetic algorithm configuration
+    Config(ConfigError),
+    /// Invalid DLX program
+    Parse(ParseError),
+    /// Invalid task file
+    Task(ParseTaskError),
+    /// Task memory region which does not fit in memory
+    Region(RegionError),
+    /// Emulator could not run a program
+    Emulator(EmulatorError),
+    /// Emulator results do not fit the task
+    Evaluation(EvaluationError),
+    /// Checkpoint could not be saved or loaded
+    #[cfg(feature = "serde")]
+    Checkpoint(CheckpointError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config(err) => write!(f, "invalid configuration: {}", err),
+            Error::Parse(err) => write!(f, "invalid program: {}", err),
+            Error::Task(err) => write!(f, "invalid task: {}", err),
+            Error::Region(err) => write!(f, "invalid task: {}", err),
+            Error::Emulator(err) => write!(f, "emulator failed: {}", err),
+            Error::Evaluation(err) => write!(f, "evaluation failed: {}", err),
+            #[cfg(feature = "serde")]
+            Error::Checkpoint(err) => write!(f, "checkpoint failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Config(err) => Some(err),
+            Error::Parse(err) => Some(err),
+            Error::Task(err) => Some(err),
+            Error::Region(err) => Some(err),
+            Error::Emulator(err) => Some(err),
+            Error::Evaluation(err) => Some(err),
+            #[cfg(feature = "serde")]
+            Error::Checkpoint(err) => Some(err),
+        }
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Error::Config(err)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<ParseTaskError> for Error {
+    fn from(err: ParseTaskError) -> Self {
+        Error::Task(err)
+    }
+}
+
+impl From<RegionError> for Error {
+    fn from(err: RegionError) -> Self {
+        Error::Region(err)
+    }
+}
+
+impl From<EmulatorError> for Error {
+    fn from(err: EmulatorError) -> Self {
+        Error::Emulator(err)
+    }
+}
+
+impl From<EvaluationError> for Error {
+    fn from(err: EvaluationError) -> Self {
+        Error::Evaluation(err)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<CheckpointError> for Error {
+    fn from(err: CheckpointError) -> Self {
+        Error::Checkpoint(err)
+    }
+}
+
+/// Kind of error in a DLX program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnknownOpcode,
+    InvalidRegister,
+    InvalidImmediate,
+    InvalidLabel,
+    DuplicateLabel,
+    /// Wrong number of operands for the opcode
+    OperandCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnknownOpcode => write!(f, "unknown opcode"),
+            ParseErrorKind::InvalidRegister => write!(f, "invalid register"),
+            ParseErrorKind::InvalidImmediate => write!(f, "invalid immediate"),
+            ParseErrorKind::InvalidLabel => write!(f, "invalid label"),
+            ParseErrorKind::DuplicateLabel => write!(f, "duplicate label"),
+            ParseErrorKind::OperandCount { expected, found } => {
+                write!(f, "expected {} operands, got {}", expected, found)
+            }
+        }
+    }
+}
+
+/// Error in a DLX program, with the position of the offending token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the error, starting at 1
+    pub line: usize,
+    /// Column of the offending token in the line, starting at 1
+    pub column: usize,
+    pub token: String,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, column: usize, token: &str) -> Self {
+        ParseError {
+            line: 1,
+            column,
+            token: token.to_string(),
+            kind,
+        }
+    }
+
+    /// Moves an error of a single instruction to its place in a program
+    pub(crate) fn offset(mut self, line: usize, columns: usize) -> Self {
+        self.line = line;
+        self.column += columns;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {} '{}'",
+            self.line, self.column, self.kind, self.token
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Reasons for which an emulator did not produce a result.
+///
+/// Programs which fault or run out of cycles still produce a failed `EmulatorResult`.
+#[derive(Debug)]
+pub enum EmulatorError {
+    /// Emulator process could not be started or talked to
+    Io(io::Error),
+    /// Emulator process exited before answering
+    Exited,
+    /// Emulator process reported an error
+    Failed(String),
+    /// Emulator did not answer in time
+    Timeout,
+    /// Emulator output could not be understood
+    InvalidOutput(String),
+    /// Emulator cannot start from the given memory image
+    UnsupportedMemory,
+}
+
+impl fmt::Display for EmulatorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmulatorError::Io(err) => write!(f, "{}", err),
+            EmulatorError::Exited => write!(f, "emulator exited"),
+            EmulatorError::Failed(message) => write!(f, "emulator raised an error: {}", message),
+            EmulatorError::Timeout => write!(f, "request timed out"),
+            EmulatorError::InvalidOutput(message) => write!(f, "invalid output: {}", message),
+            EmulatorError::UnsupportedMemory => {
+                write!(f, "emulator only starts from the reference memory image")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EmulatorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EmulatorError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EmulatorError {
+    fn from(err: io::Error) -> Self {
+        EmulatorError::Io(err)
+    }
+}
+
+/// Emulator results which cannot be evaluated on a task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvaluationError {
+    /// Number of results differs from the number of test vectors
+    ResultCount { expected: usize, found: usize },
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvaluationError::ResultCount { expected, found } => write!(
+                f,
+                "expected one result per test vector ({}), got {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EvaluationError {}
+
+/// Reasons for which a checkpoint could not be saved or loaded
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum CheckpointError {
+    /// Checkpoint file could not be read or written
+    Io(io::Error),
+    /// Checkpoint file is not a valid checkpoint
+    Format(serde_json::Error),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(err) => write!(f, "{}", err),
+            CheckpointError::Format(err) => write!(f, "invalid checkpoint: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckpointError::Io(err) => Some(err),
+            CheckpointError::Format(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<io::Error> for CheckpointError {
+    fn from(err: io::Error) -> Self {
+        CheckpointError::Io(err)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for CheckpointError {
+    fn from(err: serde_json::Error) -> Self {
+        CheckpointError::Format(err)
+    }
+}
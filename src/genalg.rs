@@ -1,13 +1,34 @@
-use std::error::Error;
-
-use crate::individual::genetic::Genetic;
-use rand::{Rng, seq::IndexedRandom};
+use crate::cache::FitnessCache;
+#[cfg(feature = "serde")]
+use crate::checkpoint::{Checkpoint, CheckpointSchedule};
+use crate::config::{
+    ConfigError, EvolutionMode, GenAlgConfig, HistoryRetention, SteadyStateReplacement,
+};
+use crate::error::Error;
+use crate::individual::genetic::{Distance, Genetic, MultiObjective};
+use crate::island::Replacement;
+use crate::observer::{GenerationView, Observer};
+use crate::pareto;
+use crate::selection::{Selection, Truncation};
+use crate::stats::{FitnessSummary, GenerationStats};
+use crate::termination::{RunState, StopCondition};
+use rand::{Rng, RngCore, SeedableRng, seq::IndexedRandom};
+use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::ops::ControlFlow;
+#[cfg(feature = "serde")]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 /// Individual that is put in genetic algorithm.
 ///
 /// Implements Genetic trait, which allows it to be used as proper population individual
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FitnessIndiv<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
     obj: T,
     fitness: f32,
@@ -36,172 +57,918 @@ impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> FitnessIndiv<T> {
     }
 }
 
+/// Outcome of a genetic algorithm run
+#[derive(Clone, Debug)]
+pub struct RunResult<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    /// Best individual found so far, including previous runs
+    pub best: FitnessIndiv<T>,
+    /// Generations run
+    pub generations: usize,
+    /// Fitness evaluations done during the run
+    pub evaluations: usize,
+    pub elapsed: Duration,
+    /// Condition which ended the run
+    pub stop_reason: StopCondition,
+}
+
+/// Progress made by a single generation
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenerationSummary {
+    /// Generations completed by the `GenAlg`, including previous runs
+    pub generation: usize,
+    /// Fitness of the best individual found so far
+    pub best_fitness: f32,
+    /// Mean fitness of the new population
+    pub mean_fitness: f32,
+    /// Fitness evaluations done during the generation
+    pub evaluations: usize,
+    /// Whether the generation found a new best individual
+    pub improved: bool,
+    /// Whether an observer asked to stop
+    pub stop_requested: bool,
+}
+
+/// Progress of a run, kept by checkpoints so a resumed run stops where the original one would
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct RunProgress {
+    pub generation: usize,
+    pub stagnant_generations: usize,
+    pub evaluations: usize,
+}
+
+/// Individual of a multi-objective run, with its place in the Pareto fronts
+#[derive(Clone, Debug)]
+pub struct ParetoIndiv<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    obj: T,
+    objectives: Vec<f32>,
+    rank: usize,
+    crowding_distance: f32,
+}
+
+impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> ParetoIndiv<T> {
+    /// Getter for object
+    pub fn obj(&self) -> &T {
+        &self.obj
+    }
+
+    /// Getter for objective values
+    pub fn objectives(&self) -> &[f32] {
+        &self.objectives
+    }
+
+    /// Getter for index of the Pareto front, 0 for non-dominated individuals
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Getter for crowding distance in its front
+    pub fn crowding_distance(&self) -> f32 {
+        self.crowding_distance
+    }
+}
+
+/// Outcome of a multi-objective run
+#[derive(Clone, Debug)]
+pub struct ParetoResult<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    /// Non-dominated individuals of the final population
+    pub front: Vec<ParetoIndiv<T>>,
+    /// Generations run
+    pub generations: usize,
+    /// Objective evaluations done during the run
+    pub evaluations: usize,
+    pub elapsed: Duration,
+}
+
+/// Keeps `size` individuals by Pareto rank, then by crowding distance, as NSGA-II does.
+///
+/// Sets the rank and crowding distance of the kept individuals, which are sorted best first.
+fn select_pareto<T: Genetic + Clone + Send + Sync + PartialEq + Eq>(
+    population: Vec<ParetoIndiv<T>>,
+    size: usize,
+) -> Vec<ParetoIndiv<T>> {
+    let objectives: Vec<Vec<f32>> = population
+        .iter()
+        .map(|indiv| indiv.objectives.clone())
+        .collect();
+    let mut population: Vec<Option<ParetoIndiv<T>>> = population.into_iter().map(Some).collect();
+    let mut selected = Vec::with_capacity(size);
+
+    for (rank, front) in pareto::non_dominated_sort(&objectives).iter().enumerate() {
+        if selected.len() >= size {
+            break;
+        }
+
+        let distances = pareto::crowding_distance(&objectives, front);
+        let mut members: Vec<(usize, f32)> = front.iter().copied().zip(distances).collect();
+        members.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (index, crowding_distance) in members.into_iter().take(size - selected.len()) {
+            let mut indiv = population[index].take().unwrap();
+            indiv.rank = rank;
+            indiv.crowding_distance = crowding_distance;
+            selected.push(indiv);
+        }
+    }
+
+    selected
+}
+
+/// Counters updated by the parallel parts of a generation
+#[derive(Debug, Default)]
+struct Counters {
+    evaluations: AtomicUsize,
+    evaluation_errors: AtomicUsize,
+    cache_hits: AtomicUsize,
+    evaluation_nanos: AtomicU64,
+    operator_nanos: AtomicU64,
+}
+
+impl Counters {
+    /// Runs `f`, adding the time it took to `nanos`
+    fn timed<R>(nanos: &AtomicU64, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = f();
+        nanos.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        result
+    }
+
+    fn evaluation_time(&self) -> Duration {
+        Duration::from_nanos(self.evaluation_nanos.load(Ordering::Relaxed))
+    }
+
+    fn operator_time(&self) -> Duration {
+        Duration::from_nanos(self.operator_nanos.load(Ordering::Relaxed))
+    }
+}
+
 /// Genetic Algorithm struct
 pub struct GenAlg<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
     population_history: Vec<Vec<FitnessIndiv<T>>>,
     current_population: Vec<FitnessIndiv<T>>,
     current_generation: usize,
     best_individual: Option<FitnessIndiv<T>>,
-    cache: Vec<T>,
+    selection: Box<dyn Selection>,
+    config: GenAlgConfig,
+    stop_condition: Option<StopCondition>,
+    counters: Counters,
+    observers: Vec<Box<dyn Observer<T>>>,
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+    /// Seeds the random generators of every generation
+    rng: ChaCha8Rng,
+    fitness_cache: Option<Arc<FitnessCache>>,
+    generation_stats: Vec<GenerationStats>,
+    distance: Option<fn(&T, &T) -> f32>,
+    /// Distinct genomes of the current population, if counted since it last changed
+    unique_genomes: Option<usize>,
+    pareto_population: Vec<ParetoIndiv<T>>,
+    /// Progress of the run interrupted by a checkpoint, continued by the next run
+    resumed_run: Option<RunProgress>,
+    #[cfg(feature = "serde")]
+    checkpoints: Option<CheckpointSchedule<T>>,
 }
 
 impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> GenAlg<T> {
-    fn check_cache(&self, ind: &T) -> Option<f32> {
-        if self.cache.is_empty() {
-            return None;
+    /// Updates self.best_individual to the best individual in the current population.
+    ///
+    /// Returns true if the best individual changed.
+    fn try_update_best_individual(&mut self) -> bool {
+        let is_better = match &self.best_individual {
+            None => true,
+            Some(v) => v.fitness() < self.current_population[0].fitness(),
+        };
+
+        if is_better {
+            self.best_individual = Some(self.current_population[0].clone());
         }
 
-        self.cache
-            .iter()
-            .find(|&indiv| indiv == ind)
-            .map(|indiv| indiv.fitness())
+        is_better
     }
 
-    /// Updates self.best_individual to the best individual in the current population
-    fn try_update_best_individual(&mut self) -> () {
-        if let None = self.best_individual {
-            self.best_individual = Some(self.current_population[0].clone());
-            return;
-        } else if let Some(v) = &self.best_individual {
-            if v.fitness() < self.current_population[0].fitness() {
-                self.best_individual = Some(self.current_population[0].clone());
+    /// Number of distinct genomes in the current population, counted once per population
+    pub(crate) fn unique_genomes(&mut self) -> usize {
+        let population = &self.current_population;
+        *self.unique_genomes.get_or_insert_with(|| {
+            (0..population.len())
+                .filter(|&i| {
+                    !population[..i]
+                        .iter()
+                        .any(|other| other.obj == population[i].obj)
+                })
+                .count()
+        })
+    }
+
+    /// Fraction of distinct genomes in the current population
+    fn diversity(&mut self) -> f32 {
+        self.unique_genomes() as f32 / self.current_population.len().max(1) as f32
+    }
+
+    /// Mean distance between two individuals of the current population
+    fn mean_distance(&self, distance: fn(&T, &T) -> f32) -> f32 {
+        let population = &self.current_population;
+        let pairs = population.len() * population.len().saturating_sub(1) / 2;
+        if pairs == 0 {
+            return 0.0;
+        }
+
+        let total: f32 = (0..population.len())
+            .into_par_iter()
+            .map(|i| {
+                population[i + 1..]
+                    .iter()
+                    .map(|other| distance(&population[i].obj, &other.obj))
+                    .sum::<f32>()
+            })
+            .sum();
+
+        total / pairs as f32
+    }
+
+    /// Evaluates the fitness of an individual created by the engine
+    fn evaluate(&self, obj: &T) -> FitnessIndiv<T> {
+        FitnessIndiv {
+            obj: obj.clone(),
+            fitness: Self::cached_fitness(obj, self.fitness_cache.as_deref(), &self.counters),
+        }
+    }
+
+    /// Looks the fitness up in the cache, evaluating and counting it on a miss.
+    ///
+    /// Failed evaluations score 0 and are counted, but not cached.
+    fn cached_fitness(obj: &T, cache: Option<&FitnessCache>, counters: &Counters) -> f32 {
+        let key = cache.and(obj.cache_key());
+
+        if let (Some(cache), Some(key)) = (cache, key)
+            && let Some(fitness) = cache.get(key)
+        {
+            counters.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return fitness;
+        }
+
+        counters.evaluations.fetch_add(1, Ordering::Relaxed);
+        match Counters::timed(&counters.evaluation_nanos, || obj.try_fitness()) {
+            Ok(fitness) => {
+                if let (Some(cache), Some(key)) = (cache, key) {
+                    cache.insert(key, fitness);
+                }
+                fitness
+            }
+            Err(_) => {
+                counters.evaluation_errors.fetch_add(1, Ordering::Relaxed);
+                0.0
             }
         }
     }
 
-    /// Validates the input parameters for the genetic algorithm
-    fn validate_ga_input(&self, selection_rate: f32, mutation_rate: f32, elite_count: usize) -> () {
-        assert!(
-            elite_count <= self.current_population.len(),
-            "elite_count cannot be greater than population size"
-        );
-        assert!(
-            (0.0..=1.0).contains(&mutation_rate),
-            "mutation_rate must be in [0.0, 1.0]"
-        );
-        assert!(
-            (0.0..=1.0).contains(&selection_rate),
-            "selection_rate must be in [0.0, 1.0]"
-        );
+    /// Caches the fitness of children and mutants by `Genetic::cache_key`.
+    ///
+    /// Cache hits do not count as fitness evaluations. The cache can be shared between runs.
+    pub fn with_fitness_cache(mut self, cache: Arc<FitnessCache>) -> Self {
+        self.fitness_cache = Some(cache);
+        self
     }
 
-    /// Getter for population history
+    /// Getter for statistics of every generation run so far
+    pub fn generation_stats(&self) -> &[GenerationStats] {
+        &self.generation_stats
+    }
+
+    /// Measures the mean `Distance` between individuals in the generation statistics.
+    ///
+    /// Compares every pair of individuals, so it is quadratic in the population size.
+    pub fn with_distance_diversity(mut self) -> Self
+    where
+        T: Distance,
+    {
+        self.distance = Some(T::distance);
+        self
+    }
+
+    /// Getter for fitness cache
+    pub fn fitness_cache(&self) -> Option<&FitnessCache> {
+        self.fitness_cache.as_deref()
+    }
+
+    /// Getter for population history, the population before every generation.
+    ///
+    /// Its content depends on the configured `HistoryRetention`, all populations are only
+    /// kept by `HistoryRetention::Full`.
     pub fn population_history(&self) -> &Vec<Vec<FitnessIndiv<T>>> {
         &self.population_history
     }
 
-    /// Main function for running the genetic algorithm
-    /// ## Arguments
-    /// * `num_of_generations` - number of generations to run
-    /// * `selection_rate` - fraction of population to select for crossover (0.0 - 1.0)
-    /// * `mutation_rate` - fraction of population to mutate (0.0 - 1.0)
-    /// * `elite_count` - number of elite individuals to keep in the population
-    /// ## Returns
-    /// * `Result<FitnessIndiv<T>, Box<dyn Error>>` - best individual found by GA
-    pub fn run_genetic_algorithm(
+    /// Stores the sorted current population as allowed by the history retention
+    fn record_history(&mut self) {
+        match self.config.history() {
+            HistoryRetention::None | HistoryRetention::StatsOnly => {}
+            HistoryRetention::BestOnly => {
+                let best = self.current_population[..1].to_vec();
+                self.population_history.push(best);
+            }
+            HistoryRetention::LastGenerations(count) => {
+                self.population_history
+                    .push(self.current_population.clone());
+                let excess = self.population_history.len().saturating_sub(count);
+                self.population_history.drain(..excess);
+            }
+            HistoryRetention::Full => {
+                let old_pop = self.current_population.clone();
+                self.population_history.push(old_pop);
+            }
+        }
+    }
+
+    /// Getter for current population, sorted from the best individual once a run started
+    pub fn current_population(&self) -> &[FitnessIndiv<T>] {
+        &self.current_population
+    }
+
+    /// Puts migrants from another population in place of individuals chosen by `replacement`.
+    ///
+    /// Elite individuals are never replaced, so migrants beyond the rest of the population
+    /// are dropped. The best individual is only updated by the following generation.
+    pub(crate) fn receive_migrants(
         &mut self,
-        num_of_generations: usize,
-        selection_rate: f32,
-        mutation_rate: f32,
+        migrants: Vec<FitnessIndiv<T>>,
+        replacement: Replacement,
+        rng: &mut dyn RngCore,
+    ) {
+        let len = self.current_population.len();
+        let elite_count = self.config.elite_count().min(len);
+        let count = migrants.len().min(len - elite_count);
+
+        let positions: Vec<usize> = match replacement {
+            Replacement::Worst => (len - count..len).collect(),
+            Replacement::Random => rand::seq::index::sample(rng, len - elite_count, count)
+                .into_iter()
+                .map(|index| index + elite_count)
+                .collect(),
+        };
+
+        for (position, migrant) in positions.into_iter().zip(migrants) {
+            self.current_population[position] = migrant;
+        }
+        self.unique_genomes = None;
+
+        self.current_population
+            .sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+    }
+
+    /// Getter for configuration
+    pub fn config(&self) -> &GenAlgConfig {
+        &self.config
+    }
+
+    /// Sets the configuration used by the following runs.
+    ///
+    /// A configuration with a seed restarts the random generator from it.
+    pub fn with_config(mut self, config: GenAlgConfig) -> Self {
+        self.set_config(config);
+        self
+    }
+
+    /// Replaces the configuration used by the following runs.
+    ///
+    /// A configuration with a seed restarts the random generator from it.
+    pub fn set_config(&mut self, config: GenAlgConfig) {
+        if let Some(seed) = config.seed() {
+            self.rng = ChaCha8Rng::seed_from_u64(seed);
+        }
+        self.config = config;
+    }
+
+    /// Adds a condition which ends runs early.
+    ///
+    /// Runs always stop after the configured number of generations, multiple conditions
+    /// end the run once any of them fires.
+    pub fn with_stop_condition(mut self, condition: StopCondition) -> Self {
+        self.stop_condition = Some(match self.stop_condition.take() {
+            Some(previous) => previous.or(condition),
+            None => condition,
+        });
+        self
+    }
+
+    /// Adds an observer notified of the progress of every run
+    pub fn with_observer(mut self, observer: impl Observer<T> + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Calls `event` on every observer with the current state.
+    ///
+    /// Returns true if any observer asked to stop, all observers are notified regardless.
+    fn notify(
+        &mut self,
+        event: impl Fn(&mut dyn Observer<T>, &GenerationView<T>) -> ControlFlow<()>,
+    ) -> bool {
+        let view = GenerationView {
+            generation: self.current_generation,
+            population: &self.current_population,
+            best: self.best_individual.as_ref().unwrap(),
+        };
+
+        let mut stop = false;
+        for observer in self.observers.iter_mut() {
+            stop |= event(observer.as_mut(), &view).is_break();
+        }
+        stop
+    }
+
+    /// Getter for number of fitness evaluations, including the initial population
+    pub fn evaluations(&self) -> usize {
+        self.counters.evaluations.load(Ordering::Relaxed)
+    }
+
+    /// Getter for number of fitness evaluations which failed and scored 0,
+    /// including the initial population
+    pub fn evaluation_errors(&self) -> usize {
+        self.counters.evaluation_errors.load(Ordering::Relaxed)
+    }
+
+    /// Sets the strategy choosing the individuals which survive and become parents.
+    ///
+    /// Defaults to `Truncation`, which keeps the best individuals.
+    pub fn with_selection(mut self, selection: impl Selection + 'static) -> Self {
+        self.selection = Box::new(selection);
+        self
+    }
+
+    /// Selects `count` survivors from the sorted current population.
+    ///
+    /// The best `elite_count` individuals always survive, the rest is chosen by the selection strategy.
+    fn select_survivors(
+        &self,
+        count: usize,
         elite_count: usize,
-    ) -> Result<FitnessIndiv<T>, Box<dyn Error>> {
-        self.validate_ga_input(selection_rate, mutation_rate, elite_count);
+        rng: &mut dyn RngCore,
+    ) -> Vec<FitnessIndiv<T>> {
+        let elite_count = elite_count.min(count);
+        let (elite, rest) = self.current_population.split_at(elite_count);
+        let fitness: Vec<f32> = rest.iter().map(|indiv| indiv.fitness()).collect();
+
+        // Nothing is left to select from once the whole population is elite
+        let selected = self
+            .selection
+            .select(&fitness, count - elite_count, rng)
+            .unwrap_or_default();
+
+        elite
+            .iter()
+            .cloned()
+            .chain(selected.into_iter().map(|index| rest[index].clone()))
+            .collect()
+    }
 
-        let selected_count =
-            (self.current_population.len() as f32 * selection_rate).floor() as usize;
+    /// Creates a child from `parents`, by crossing them over in order
+    /// or, with probability `1 - crossover_rate`, by copying the first one
+    fn make_child(&self, parents: &[&FitnessIndiv<T>], rng: &mut dyn RngCore) -> FitnessIndiv<T> {
+        match self.crossover(parents, rng) {
+            Some(child) => self.evaluate(&child),
+            None => parents[0].clone(),
+        }
+    }
 
-        assert!(
-            selected_count >= 2,
-            "selection_rate too small. selection_rate should be large enough, to select at least 2 individuals."
-        );
+    /// Crosses `parents` over in order with probability `crossover_rate`,
+    /// returns `None` if the child is a copy of the first parent
+    fn crossover(&self, parents: &[&FitnessIndiv<T>], rng: &mut dyn RngCore) -> Option<T> {
+        if rng.random::<f32>() >= self.config.crossover_rate() {
+            return None;
+        }
 
-        let population_size = self.current_population.len();
+        Some(Counters::timed(&self.counters.operator_nanos, || {
+            parents[2..].iter().fold(
+                parents[0].obj.crossover(&parents[1].obj, rng),
+                |child, parent| child.crossover(&parent.obj, rng),
+            )
+        }))
+    }
+
+    /// Main function for running the genetic algorithm, with parameters taken from the configuration.
+    ///
+    /// Runs until the configured number of generations or until a stop condition fires.
+    /// Generations are generational or steady-state, as set by the configured `EvolutionMode`.
+    /// ## Returns
+    /// * `Result<RunResult<T>, Error>` - best individual found by GA and why the run stopped,
+    ///   or the error if the configuration is invalid for the population
+    pub fn run_genetic_algorithm(&mut self) -> Result<RunResult<T>, Error> {
+        let mut generations = self.generations();
+
+        for summary in &mut generations {
+            summary?;
+        }
+
+        Ok(generations
+            .into_result()
+            .expect("finished run has a stop reason"))
+    }
+
+    /// Starts a run yielding a summary after every generation.
+    ///
+    /// The run ends like `run_genetic_algorithm`, `into_result` then returns its outcome.
+    pub fn generations(&mut self) -> Generations<'_, T> {
+        let mut stop_condition = StopCondition::Generations(self.config.generations());
+        if let Some(condition) = &self.stop_condition {
+            stop_condition = stop_condition.or(condition.clone());
+        }
+
+        let resumed = self.resumed_run.take().unwrap_or(RunProgress {
+            generation: 0,
+            stagnant_generations: 0,
+            evaluations: 0,
+        });
+
+        Generations {
+            gen_alg: self,
+            stop_condition,
+            start: None,
+            resumed_evaluations: resumed.evaluations,
+            generation: resumed.generation,
+            stagnant_generations: resumed.stagnant_generations,
+            stop_requested: false,
+            stop_reason: None,
+            finished: false,
+        }
+    }
+
+    /// Advances the population by exactly one generation.
+    ///
+    /// Stop conditions are not checked and observers are only notified of the generation,
+    /// so the caller decides when to stop.
+    pub fn step(&mut self) -> Result<GenerationSummary, Error> {
+        self.prepare()?;
+        let summary = self.advance(true);
+
+        #[cfg(feature = "serde")]
+        self.checkpoint_if_due(None)?;
+
+        Ok(summary)
+    }
+
+    /// Validates the configuration, sorts the population and sets the best individual
+    fn prepare(&mut self) -> Result<(), Error> {
+        self.config
+            .validate_for_population(self.current_population.len())?;
+
+        self.thread_pool = match (self.config.threads(), self.thread_pool.take()) {
+            (None, _) => None,
+            (Some(threads), Some(pool)) if pool.current_num_threads() == threads => Some(pool),
+            (Some(threads), _) => Some(Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|err| ConfigError::ThreadPool(err.to_string()))?,
+            )),
+        };
 
         // sort population by fitness
         self.current_population
-            .sort_by(|a, b| b.fitness().partial_cmp(&a.fitness()).unwrap());
+            .sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
 
         self.try_update_best_individual();
 
-        for generation in 0..num_of_generations {
-            let old_pop = self.current_population.clone();
-            self.population_history.push(old_pop);
+        Ok(())
+    }
 
-            // get top selected individuals
-            //self.current_population.truncate(selected_count);
+    /// Runs one generation on the configured thread pool
+    fn advance(&mut self, mutate: bool) -> GenerationSummary {
+        match self.thread_pool.clone() {
+            Some(pool) => pool.install(|| self.run_generation(mutate)),
+            None => self.run_generation(mutate),
+        }
+    }
+
+    /// Runs one generation on the current thread pool, the population has to be sorted
+    fn run_generation(&mut self, mutate: bool) -> GenerationSummary {
+        let start_evaluations = self.evaluations();
+        let start_evaluation_errors = self.evaluation_errors();
+        let start_cache_hits = self.counters.cache_hits.load(Ordering::Relaxed);
+        let start_evaluation_time = self.counters.evaluation_time();
+        let start_operator_time = self.counters.operator_time();
+
+        self.record_history();
+
+        match self.config.mode() {
+            EvolutionMode::Generational => self.breed_generation(mutate),
+            EvolutionMode::SteadyState {
+                offspring,
+                replacement,
+            } => self.breed_steady_state(offspring, replacement, mutate),
+        }
+        self.unique_genomes = None;
+
+        // sort new population by fitness
+        self.current_population
+            .sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+
+        let mut stop_requested = self.notify(|observer, view| observer.on_evaluation(view));
+
+        // update best
+        let improved = self.try_update_best_individual();
 
-            // roulette wheel selection
-            // let rw: RouletteWheel<T> = self
-            //     .current_population
-            //     .iter()
-            //     .map(|indiv| indiv.into_tuple())
-            //     .collect();
+        self.current_generation += 1;
 
-            // self.current_population = rw
-            //     .into_iter()
-            //     .map(|(f, ind)| FitnessIndiv {
-            //         obj: ind,
-            //         fitness: f,
-            //     })
-            //     .collect();
+        let fitness: Vec<f32> = self
+            .current_population
+            .iter()
+            .map(|i| i.fitness())
+            .collect();
+        let summary = FitnessSummary::new(&fitness);
+        if self.config.history().keeps_stats() {
+            let unique_genomes = self.unique_genomes();
+            self.generation_stats.push(GenerationStats {
+                generation: self.current_generation,
+                min_fitness: summary.min,
+                max_fitness: summary.max,
+                mean_fitness: summary.mean,
+                median_fitness: summary.median,
+                stddev_fitness: summary.stddev,
+                unique_genomes,
+                evaluations: self.evaluations() - start_evaluations,
+                evaluation_errors: self.evaluation_errors() - start_evaluation_errors,
+                cache_hits: self.counters.cache_hits.load(Ordering::Relaxed) - start_cache_hits,
+                evaluation_time: self.counters.evaluation_time() - start_evaluation_time,
+                operator_time: self.counters.operator_time() - start_operator_time,
+                mean_distance: self.distance.map(|distance| self.mean_distance(distance)),
+            });
+        }
 
-            self.current_population.truncate(selected_count);
+        stop_requested |= self.notify(|observer, view| observer.on_generation(view));
 
-            // crossover
-            let needed = population_size - self.current_population.len();
-            let parents_pool = &self.current_population[..selected_count];
+        GenerationSummary {
+            generation: self.current_generation,
+            best_fitness: self.best_individual.as_ref().unwrap().fitness(),
+            mean_fitness: summary.mean,
+            evaluations: self.evaluations() - start_evaluations,
+            improved,
+            stop_requested,
+        }
+    }
+
+    /// Replaces the population by the selected individuals and their children,
+    /// then mutates everything but the elite
+    fn breed_generation(&mut self, mutate: bool) {
+        let mutation_rate = self.config.mutation_rate();
+        let elite_count = self.config.elite_count();
+        let parent_count = self.config.parent_count();
+
+        let population_size = self.current_population.len();
+        let selected_count = self.config.selected_count(population_size);
+
+        // every task gets its own stream, so results do not depend on thread scheduling
+        let selection_seed: u64 = self.rng.random();
+        let crossover_seed: u64 = self.rng.random();
+        let mutation_seed: u64 = self.rng.random();
+
+        // selected individuals survive and become parents
+        self.current_population = Counters::timed(&self.counters.operator_nanos, || {
+            self.select_survivors(
+                selected_count,
+                elite_count,
+                &mut task_rng(selection_seed, 0),
+            )
+        });
+
+        // crossover
+        let needed = population_size - self.current_population.len();
+        let parents_pool = &self.current_population[..selected_count];
+
+        let new_children: Vec<FitnessIndiv<T>> = (0..needed)
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = task_rng(crossover_seed, i);
+                let parents = parents_pool
+                    .choose_multiple(&mut rng, parent_count)
+                    .collect::<Vec<_>>();
+
+                self.make_child(&parents, &mut rng)
+            })
+            .collect();
+
+        self.current_population.extend(new_children);
+
+        // mutation
+        if mutate {
+            let counters = &self.counters;
+            let cache = self.fitness_cache.as_deref();
+            self.current_population
+                .as_mut_slice()
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(i, indiv)| {
+                    if i >= elite_count {
+                        let mut rng = task_rng(mutation_seed, i);
+                        if rng.random::<f32>() < mutation_rate {
+                            Counters::timed(&counters.operator_nanos, || {
+                                indiv.obj.mutate(&mut rng)
+                            });
+                            indiv.fitness = Self::cached_fitness(&indiv.obj, cache, counters);
+                        }
+                    }
+                });
+        }
+    }
+
+    /// Creates `offspring` children of the selected individuals, each replacing
+    /// an individual chosen by `replacement`
+    fn breed_steady_state(
+        &mut self,
+        offspring: usize,
+        replacement: SteadyStateReplacement,
+        mutate: bool,
+    ) {
+        let mutation_rate = self.config.mutation_rate();
+        let elite_count = self.config.elite_count();
+        let parent_count = self.config.parent_count();
+
+        let population_size = self.current_population.len();
+        let selected_count = self.config.selected_count(population_size);
+
+        let selection_seed: u64 = self.rng.random();
+        let offspring_seed: u64 = self.rng.random();
+        let replacement_seed: u64 = self.rng.random();
+
+        let parents_pool = Counters::timed(&self.counters.operator_nanos, || {
+            self.select_survivors(
+                selected_count,
+                elite_count,
+                &mut task_rng(selection_seed, 0),
+            )
+        });
+
+        // children are mutated before their only evaluation
+        let children: Vec<FitnessIndiv<T>> = (0..offspring)
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = task_rng(offspring_seed, i);
+                let parents = parents_pool
+                    .choose_multiple(&mut rng, parent_count)
+                    .collect::<Vec<_>>();
+
+                let mut child = self.crossover(&parents, &mut rng);
+                if mutate && rng.random::<f32>() < mutation_rate {
+                    let obj = child.get_or_insert_with(|| parents[0].obj.clone());
+                    Counters::timed(&self.counters.operator_nanos, || obj.mutate(&mut rng));
+                }
+
+                match child {
+                    Some(obj) => self.evaluate(&obj),
+                    None => parents[0].clone(),
+                }
+            })
+            .collect();
+
+        let mut rng = task_rng(replacement_seed, 0);
+        let replaceable = elite_count..population_size;
+        for child in children {
+            let fitness = |&index: &usize| self.current_population[index].fitness();
+            let position = match replacement {
+                SteadyStateReplacement::Worst => replaceable
+                    .clone()
+                    .min_by(|a, b| fitness(a).total_cmp(&fitness(b))),
+                SteadyStateReplacement::TournamentLoser(size) => (0..size)
+                    .map(|_| rng.random_range(replaceable.clone()))
+                    .min_by(|a, b| fitness(a).total_cmp(&fitness(b))),
+            };
+
+            self.current_population[position.unwrap()] = child;
+        }
+    }
+
+    /// Runs the NSGA-II multi-objective algorithm for the configured number of generations.
+    ///
+    /// Parents are chosen by binary tournaments on Pareto rank and crowding distance, every
+    /// generation keeps the best of the parents and children. Uses the rates, seed and threads
+    /// of the configuration, while selection strategy, elite count, stop conditions, observers,
+    /// history and fitness cache only apply to single objective runs.
+    ///
+    /// The first run starts from the current population, following ones continue from
+    /// the population left by the previous multi-objective run.
+    /// ## Returns
+    /// * `Result<ParetoResult<T>, Error>` - Pareto front of the final population,
+    ///   or the error if the configuration is invalid for the population
+    pub fn run_multi_objective(&mut self) -> Result<ParetoResult<T>, Error>
+    where
+        T: MultiObjective,
+    {
+        self.prepare()?;
+
+        let start_timer = Instant::now();
+        let start_evaluations = self.evaluations();
+
+        match self.thread_pool.clone() {
+            Some(pool) => pool.install(|| self.run_pareto_generations()),
+            None => self.run_pareto_generations(),
+        }
 
-            let new_children: Vec<FitnessIndiv<T>> = (0..needed)
+        Ok(ParetoResult {
+            front: self
+                .pareto_population
+                .iter()
+                .filter(|indiv| indiv.rank == 0)
+                .cloned()
+                .collect(),
+            generations: self.config.generations(),
+            evaluations: self.evaluations() - start_evaluations,
+            elapsed: start_timer.elapsed(),
+        })
+    }
+
+    /// Getter for population of the multi-objective runs, sorted by rank and crowding distance
+    pub fn pareto_population(&self) -> &[ParetoIndiv<T>] {
+        &self.pareto_population
+    }
+
+    /// Evaluates the objectives of an individual created by the engine
+    fn evaluate_objectives(&self, obj: T) -> ParetoIndiv<T>
+    where
+        T: MultiObjective,
+    {
+        self.counters.evaluations.fetch_add(1, Ordering::Relaxed);
+        let objectives = Counters::timed(&self.counters.evaluation_nanos, || obj.objectives());
+
+        ParetoIndiv {
+            obj,
+            objectives,
+            rank: 0,
+            crowding_distance: 0.0,
+        }
+    }
+
+    /// Picks the better of two random individuals of the multi-objective population
+    fn pareto_tournament(&self, rng: &mut dyn RngCore) -> &ParetoIndiv<T> {
+        let population = &self.pareto_population;
+        let a = &population[rng.random_range(0..population.len())];
+        let b = &population[rng.random_range(0..population.len())];
+
+        match pareto::crowded_cmp(a.rank, a.crowding_distance, b.rank, b.crowding_distance) {
+            std::cmp::Ordering::Greater => b,
+            _ => a,
+        }
+    }
+
+    /// Runs NSGA-II generations on the current thread pool
+    fn run_pareto_generations(&mut self)
+    where
+        T: MultiObjective,
+    {
+        if self.pareto_population.is_empty() {
+            let start_population: Vec<T> = self
+                .current_population
+                .iter()
+                .map(|indiv| indiv.obj.clone())
+                .collect();
+            let evaluated = start_population
                 .into_par_iter()
-                .map_init(rand::rng, |rng, _| {
-                    let parents = parents_pool.choose_multiple(rng, 2).collect::<Vec<_>>();
-                    let child = parents[0].obj.crossover(&parents[1].obj);
-
-                    FitnessIndiv::new(&child)
-                    // let cached_fitness = self.check_cache(&child);
-
-                    // match cached_fitness {
-                    //     Some(fitness) => FitnessIndiv {
-                    //         obj: child,
-                    //         fitness: fitness,
-                    //     },
-                    //     None => FitnessIndiv::new(&child),
-                    // }
-                })
+                .map(|obj| self.evaluate_objectives(obj))
                 .collect();
+            self.pareto_population = select_pareto(evaluated, self.current_population.len());
+        }
 
-            self.current_population.extend(new_children);
-
-            // mutation, except in last generation
-            if generation != num_of_generations - 1 {
-                if generation != num_of_generations - 1 {
-                    self.current_population
-                        .as_mut_slice()
-                        .par_iter_mut()
-                        .enumerate()
-                        .for_each(|(i, indiv)| {
-                            if i >= elite_count {
-                                let mut rng = rand::rng(); // replace with correct random generator
-                                if rng.random::<f32>() < mutation_rate {
-                                    indiv.obj.mutate();
-                                    indiv.fitness = indiv.obj.fitness();
-                                }
-                            }
-                        });
-                }
-            }
+        let population_size = self.pareto_population.len();
+        let crossover_rate = self.config.crossover_rate();
+        let mutation_rate = self.config.mutation_rate();
 
-            // sort new population by fitness
-            self.current_population
-                .sort_by(|a, b| b.fitness().partial_cmp(&a.fitness()).unwrap());
+        for _ in 0..self.config.generations() {
+            let offspring_seed: u64 = self.rng.random();
 
-            // update best
-            self.try_update_best_individual();
+            // A parent which is neither crossed over nor mutated is already in the population,
+            // a copy of it would only crowd the front, so it adds no child
+            let offspring: Vec<ParetoIndiv<T>> = (0..population_size)
+                .into_par_iter()
+                .filter_map(|i| {
+                    let mut rng = task_rng(offspring_seed, i);
+                    let first = self.pareto_tournament(&mut rng);
+                    let second = self.pareto_tournament(&mut rng);
+
+                    let crossed = rng.random::<f32>() < crossover_rate;
+                    let mutated = rng.random::<f32>() < mutation_rate;
+                    if !crossed && !mutated {
+                        return None;
+                    }
+
+                    let child = Counters::timed(&self.counters.operator_nanos, || {
+                        let mut child = if crossed {
+                            first.obj.crossover(&second.obj, &mut rng)
+                        } else {
+                            first.obj.clone()
+                        };
+                        if mutated {
+                            child.mutate(&mut rng);
+                        }
+                        child
+                    });
+
+                    Some(self.evaluate_objectives(child))
+                })
+                .collect();
 
+            let mut combined = std::mem::take(&mut self.pareto_population);
+            combined.extend(offspring);
+            self.pareto_population = select_pareto(combined, population_size);
             self.current_generation += 1;
         }
-
-        Ok(self.best_individual.as_ref().unwrap().clone())
     }
 
     /// Creates a new genetic algorithm instance
@@ -211,37 +978,388 @@ impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> GenAlg<T> {
     /// ## Returns
     /// * `GenAlg<T>` - new genetic algorithm instance
     pub fn new(population_size: usize, initial_population: Option<&Vec<T>>) -> Self {
-        let mut start_population: Vec<FitnessIndiv<T>> = Vec::with_capacity(population_size);
+        Self::from_config(population_size, initial_population, GenAlgConfig::default())
+    }
 
+    /// Creates a new genetic algorithm instance with a configuration.
+    ///
+    /// Unlike setting the configuration later, its seed also makes the generated
+    /// initial population reproducible.
+    /// ## Arguments
+    /// * `population_size` - size of the population
+    /// * `initial_population` - optional initial population
+    /// * `config` - configuration of the runs
+    pub fn from_config(
+        population_size: usize,
+        initial_population: Option<&Vec<T>>,
+        config: GenAlgConfig,
+    ) -> Self {
         match initial_population {
             Some(init_pop) => {
-                start_population = init_pop.into_iter().map(|a| FitnessIndiv::new(a)).collect();
+                let rng = Self::config_rng(&config);
+                Self::with_population(init_pop.clone(), config, rng)
             }
-            None => start_population
-                .extend((0..population_size).map(|_| FitnessIndiv::new(&T::generate()))),
+            None => Self::from_generator(population_size, config, T::generate),
+        }
+    }
+
+    /// Creates a new genetic algorithm instance whose initial population is made by `generate`
+    /// instead of `Genetic::generate`.
+    ///
+    /// Used when new individuals need context the trait can't give them, such as the task
+    /// and emulator backend of DLX individuals.
+    /// ## Arguments
+    /// * `population_size` - size of the population
+    /// * `config` - configuration of the runs, its seed also seeds `generate`
+    /// * `generate` - creates one individual from the passed generator
+    pub fn from_generator(
+        population_size: usize,
+        config: GenAlgConfig,
+        mut generate: impl FnMut(&mut dyn RngCore) -> T,
+    ) -> Self {
+        let mut rng = Self::config_rng(&config);
+
+        let start_population = (0..population_size).map(|_| generate(&mut rng)).collect();
+
+        Self::with_population(start_population, config, rng)
+    }
+
+    fn config_rng(config: &GenAlgConfig) -> ChaCha8Rng {
+        match config.seed() {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_rng(&mut rand::rng()),
         }
+    }
+
+    /// Evaluates the initial population and creates the instance around it
+    fn with_population(start_population: Vec<T>, config: GenAlgConfig, rng: ChaCha8Rng) -> Self {
+        let counters = Counters::default();
+        let start_population = start_population
+            .into_iter()
+            .map(|obj| FitnessIndiv {
+                fitness: Self::cached_fitness(&obj, None, &counters),
+                obj,
+            })
+            .collect();
 
         Self {
             population_history: Vec::new(),
             current_population: start_population,
             current_generation: 0,
             best_individual: None,
-            cache: Vec::new(),
+            selection: Box::new(Truncation),
+            config,
+            stop_condition: None,
+            counters,
+            observers: Vec::new(),
+            thread_pool: None,
+            rng,
+            fitness_cache: None,
+            generation_stats: Vec::new(),
+            distance: None,
+            unique_genomes: None,
+            pareto_population: Vec::new(),
+            resumed_run: None,
+            #[cfg(feature = "serde")]
+            checkpoints: None,
+        }
+    }
+
+    /// Saves a checkpoint to `path` every `interval` generations, replacing the previous one.
+    ///
+    /// Checkpoints are saved after the generations which are a multiple of `interval`,
+    /// counting the generations of previous runs. A failed save ends the run with the error.
+    ///
+    /// Fails if `interval` is 0.
+    #[cfg(feature = "serde")]
+    pub fn with_checkpoints(
+        mut self,
+        path: impl Into<PathBuf>,
+        interval: usize,
+    ) -> Result<Self, ConfigError>
+    where
+        T: Serialize,
+    {
+        if interval == 0 {
+            return Err(ConfigError::ZeroCheckpointInterval);
+        }
+
+        self.checkpoints = Some(CheckpointSchedule {
+            path: path.into(),
+            interval,
+            save: |gen_alg, run, path| gen_alg.snapshot(run).save(path),
+        });
+        Ok(self)
+    }
+
+    /// Returns the current state, from which `from_checkpoint` continues
+    #[cfg(feature = "serde")]
+    pub fn checkpoint(&self) -> Checkpoint<T> {
+        self.snapshot(None)
+    }
+
+    /// Creates a genetic algorithm instance continuing from a checkpoint.
+    ///
+    /// `rebind` is applied to every individual of the checkpoint to restore what serialization
+    /// dropped, like the task and emulator backend of DLX individuals. Individuals which
+    /// serialize completely can pass `std::convert::identity`.
+    ///
+    /// If the checkpoint was saved during a run, the next run continues it and stops
+    /// where the interrupted run would have. Everything the checkpoint does not keep
+    /// has to be set again, like on a new instance.
+    #[cfg(feature = "serde")]
+    pub fn from_checkpoint(checkpoint: Checkpoint<T>, mut rebind: impl FnMut(T) -> T) -> Self {
+        let mut rebind_indiv = |indiv: FitnessIndiv<T>| FitnessIndiv {
+            obj: rebind(indiv.obj),
+            fitness: indiv.fitness,
+        };
+        let mut gen_alg = Self::from_config(0, None, checkpoint.config);
+
+        gen_alg.current_population = checkpoint
+            .population
+            .into_iter()
+            .map(&mut rebind_indiv)
+            .collect();
+        gen_alg.best_individual = checkpoint.best.map(rebind_indiv);
+        gen_alg.current_generation = checkpoint.generation;
+        gen_alg.counters.evaluations = AtomicUsize::new(checkpoint.evaluations);
+        gen_alg.counters.evaluation_errors = AtomicUsize::new(checkpoint.evaluation_errors);
+        gen_alg.counters.cache_hits = AtomicUsize::new(checkpoint.cache_hits);
+        gen_alg.rng = checkpoint.rng;
+        gen_alg.generation_stats = checkpoint.generation_stats;
+        gen_alg.resumed_run = checkpoint.run;
+        gen_alg
+    }
+
+    /// Loads a checkpoint file saved by `with_checkpoints` and continues from it,
+    /// see `from_checkpoint` for `rebind`
+    #[cfg(feature = "serde")]
+    pub fn resume(path: &Path, rebind: impl FnMut(T) -> T) -> Result<Self, Error>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(Self::from_checkpoint(Checkpoint::load(path)?, rebind))
+    }
+
+    #[cfg(feature = "serde")]
+    fn snapshot(&self, run: Option<RunProgress>) -> Checkpoint<T> {
+        Checkpoint {
+            config: self.config.clone(),
+            population: self.current_population.clone(),
+            best: self.best_individual.clone(),
+            generation: self.current_generation,
+            evaluations: self.evaluations(),
+            evaluation_errors: self.evaluation_errors(),
+            cache_hits: self.counters.cache_hits.load(Ordering::Relaxed),
+            rng: self.rng.clone(),
+            generation_stats: self.generation_stats.clone(),
+            run,
+        }
+    }
+
+    /// Saves a checkpoint if the schedule has one due after the current generation
+    #[cfg(feature = "serde")]
+    fn checkpoint_if_due(&self, run: Option<RunProgress>) -> Result<(), Error> {
+        match &self.checkpoints {
+            Some(schedule) if self.current_generation.is_multiple_of(schedule.interval) => {
+                Ok((schedule.save)(self, run, &schedule.path)?)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns total fitness of the current population
+    pub fn get_total_fitness(&self) -> f32 {
+        self.current_population
+            .iter()
+            .map(|ind| ind.fitness())
+            .sum()
+    }
+
+    /// Returns total fitness of the given population
+    pub fn calc_total_fitness(population: &[FitnessIndiv<T>]) -> f32 {
+        population.iter().map(|ind| ind.fitness()).sum()
+    }
+}
+
+/// Run of a `GenAlg`, yielding a summary after every generation.
+///
+/// Created by `GenAlg::generations`. The first call to `next` validates the configuration
+/// and yields the error if it is invalid. Observers are told about the end of the run
+/// only if the iterator runs until the end.
+pub struct Generations<'a, T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    gen_alg: &'a mut GenAlg<T>,
+    stop_condition: StopCondition,
+    /// Time and evaluation count at the start of the run, set by the first call to `next`
+    start: Option<(Instant, usize)>,
+    /// Evaluations done before the checkpoint the run was resumed from
+    resumed_evaluations: usize,
+    generation: usize,
+    stagnant_generations: usize,
+    stop_requested: bool,
+    stop_reason: Option<StopCondition>,
+    finished: bool,
+}
+
+impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> Generations<'_, T> {
+    /// Condition which ended the run, `None` while it is running
+    pub fn stop_reason(&self) -> Option<&StopCondition> {
+        self.stop_reason.as_ref()
+    }
+
+    /// Getter for number of generations run so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Getter for the running `GenAlg`
+    pub fn gen_alg(&self) -> &GenAlg<T> {
+        self.gen_alg
+    }
+
+    pub(crate) fn gen_alg_mut(&mut self) -> &mut GenAlg<T> {
+        self.gen_alg
+    }
+
+    /// Returns the outcome of the run, or `None` if it has not ended
+    pub fn into_result(self) -> Option<RunResult<T>> {
+        let (start_timer, start_evaluations) = self.start?;
+
+        Some(RunResult {
+            best: self.gen_alg.best_individual.as_ref()?.clone(),
+            generations: self.generation,
+            evaluations: self.gen_alg.evaluations() - start_evaluations,
+            elapsed: start_timer.elapsed(),
+            stop_reason: self.stop_reason?,
+        })
+    }
+
+    /// Ends the run if its stop condition is met, without waiting for the next call to `next`.
+    ///
+    /// Returns true if the run has ended, `next` then returns `None`. Runs which have not
+    /// started yet are never ended.
+    pub fn finish_if_stopped(&mut self) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        match self.start {
+            Some((start_timer, start_evaluations)) => {
+                self.stop_if_due(start_timer, start_evaluations)
+            }
+            None => false,
+        }
+    }
+
+    /// Ends the run and tells the observers if a stop condition is met
+    fn stop_if_due(&mut self, start_timer: Instant, start_evaluations: usize) -> bool {
+        let Some(stop_reason) = self.check_stop(start_timer, start_evaluations) else {
+            return false;
+        };
+
+        self.gen_alg.notify(|observer, view| {
+            observer.on_end(view, &stop_reason);
+            ControlFlow::Continue(())
+        });
+        self.stop_reason = Some(stop_reason);
+        self.finished = true;
+        true
+    }
+
+    /// Returns the condition which ends the run before the next generation, if any
+    fn check_stop(
+        &mut self,
+        start_timer: Instant,
+        start_evaluations: usize,
+    ) -> Option<StopCondition> {
+        if self.stop_requested {
+            return Some(StopCondition::Requested);
+        }
+
+        // Counting distinct genomes compares every pair, so it is skipped if nothing reads it
+        let diversity = if self.stop_condition.needs_diversity() {
+            self.gen_alg.diversity()
+        } else {
+            f32::NAN
+        };
+
+        let gen_alg = &self.gen_alg;
+        let state = RunState {
+            generation: self.generation,
+            best_fitness: gen_alg.best_individual.as_ref().unwrap().fitness(),
+            stagnant_generations: self.stagnant_generations,
+            elapsed: start_timer.elapsed(),
+            evaluations: gen_alg.evaluations() - start_evaluations,
+            diversity,
+        };
+
+        self.stop_condition.check(&state).cloned()
+    }
+}
+
+impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> Iterator for Generations<'_, T> {
+    type Item = Result<GenerationSummary, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let (start_timer, start_evaluations) = match self.start {
+            Some(start) => start,
+            None => {
+                let start = (
+                    Instant::now(),
+                    self.gen_alg.evaluations() - self.resumed_evaluations,
+                );
+                if let Err(err) = self.gen_alg.prepare() {
+                    // the run cannot start, end it without a result
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+
+                self.start = Some(start);
+                self.stop_requested = self
+                    .gen_alg
+                    .notify(|observer, view| observer.on_start(view));
+                start
+            }
+        };
+
+        if self.stop_if_due(start_timer, start_evaluations) {
+            return None;
+        }
+
+        // mutation, except in last generation
+        let mutate = self.generation + 1 != self.gen_alg.config.generations();
+        let summary = self.gen_alg.advance(mutate);
+
+        if summary.improved {
+            self.stagnant_generations = 0;
+        } else {
+            self.stagnant_generations += 1;
+        }
+        self.generation += 1;
+        self.stop_requested = summary.stop_requested;
+
+        #[cfg(feature = "serde")]
+        if let Err(err) = self.gen_alg.checkpoint_if_due(Some(RunProgress {
+            generation: self.generation,
+            stagnant_generations: self.stagnant_generations,
+            evaluations: self.gen_alg.evaluations() - start_evaluations,
+        })) {
+            return Some(Err(err));
         }
-    }
 
-    /// Returns total fitness of the current population
-    pub fn get_total_fitness(&self) -> f32 {
-        self.current_population
-            .iter()
-            .map(|ind| ind.fitness())
-            .sum()
+        Some(Ok(summary))
     }
+}
 
-    /// Returns total fitness of the given population
-    pub fn calc_total_fitness(population: &Vec<FitnessIndiv<T>>) -> f32 {
-        population.iter().map(|ind| ind.fitness()).sum()
-    }
+/// Random generator of one task of a generation, independent of the thread running it
+fn task_rng(seed: u64, task: usize) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(task as u64);
+    rng
 }
 
 #[cfg(test)]
@@ -249,31 +1367,31 @@ mod tests {
     use std::time::Instant;
 
     use super::*;
+    use crate::observer::{ProgressPrinter, StatsCollector};
     use rand::Rng;
 
     const MAX_RAND: i32 = 1000;
 
-    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     struct DummyGenetic {
         a: i32,
         b: i32,
     }
 
     impl Genetic for DummyGenetic {
-        fn generate() -> Self {
-            let mut rng = rand::rng();
+        fn generate(rng: &mut dyn RngCore) -> Self {
             let a = rng.random_range(0..MAX_RAND);
             let b = rng.random_range(0..MAX_RAND);
 
-            DummyGenetic { a: a, b: b }
+            DummyGenetic { a, b }
         }
 
         fn fitness(&self) -> f32 {
             1000.0 / 2.0_f32.powf(((self.a - self.b).abs() as f32).sqrt())
         }
 
-        fn crossover(&self, other: &Self) -> Self {
-            let mut rng = rand::rng();
+        fn crossover(&self, other: &Self, rng: &mut dyn RngCore) -> Self {
             if rng.random_bool(0.5) {
                 DummyGenetic {
                     a: self.a,
@@ -287,8 +1405,15 @@ mod tests {
             }
         }
 
-        fn mutate(&mut self) -> () {
-            let mut rng = rand::rng();
+        fn cache_key(&self) -> Option<u64> {
+            use std::hash::{DefaultHasher, Hash, Hasher};
+
+            let mut hasher = DefaultHasher::new();
+            self.hash(&mut hasher);
+            Some(hasher.finish())
+        }
+
+        fn mutate(&mut self, rng: &mut dyn RngCore) {
             match rng.random_bool(0.5) {
                 true => self.a = rng.random_range(0..MAX_RAND),
                 false => self.b = rng.random_range(0..MAX_RAND),
@@ -296,6 +1421,16 @@ mod tests {
         }
     }
 
+    impl MultiObjective for DummyGenetic {
+        /// Conflicting targets for `a`, the front has `b` at 300 and `a` between 300 and 700
+        fn objectives(&self) -> Vec<f32> {
+            vec![
+                -((self.a - 300).abs() + (self.b - 300).abs()) as f32,
+                -(self.a - 700).abs() as f32,
+            ]
+        }
+    }
+
     const POP_SIZE: usize = 100;
     const NUM_GENS: usize = 100;
 
@@ -312,21 +1447,40 @@ mod tests {
 
     const SPEED_TEST_BULK_COUNT: usize = 1000;
 
-    fn are_vals_in_range(vect: &Vec<FitnessIndiv<DummyGenetic>>) -> bool {
+    /// Runs the genetic algorithm with the given parameters and defaults for the rest
+    fn run(
+        gen_alg: &mut GenAlg<DummyGenetic>,
+        generations: usize,
+        selection_rate: f32,
+        mutation_rate: f32,
+        elite_count: usize,
+    ) -> Result<RunResult<DummyGenetic>, Error> {
+        gen_alg.set_config(
+            GenAlgConfig::builder()
+                .with_generations(generations)
+                .with_selection_rate(selection_rate)
+                .with_mutation_rate(mutation_rate)
+                .with_elite_count(elite_count)
+                .build()?,
+        );
+        gen_alg.run_genetic_algorithm()
+    }
+
+    fn are_vals_in_range(vect: &[FitnessIndiv<DummyGenetic>]) -> bool {
         vect.iter().all(|individual| {
             (0..MAX_RAND).contains(&individual.obj.a) && (0..MAX_RAND).contains(&individual.obj.b)
         })
     }
 
-    fn are_vals_randomly_generated(vect: &Vec<FitnessIndiv<DummyGenetic>>) -> bool {
+    fn are_vals_randomly_generated(vect: &[FitnessIndiv<DummyGenetic>]) -> bool {
         !(vect
             .iter()
             .all(|x| x.obj.a == vect[0].obj.a && x.obj.b == vect[0].obj.b))
     }
 
     fn are_populations_same(
-        vect: &Vec<FitnessIndiv<DummyGenetic>>,
-        other: &Vec<FitnessIndiv<DummyGenetic>>,
+        vect: &[FitnessIndiv<DummyGenetic>],
+        other: &[FitnessIndiv<DummyGenetic>],
     ) -> bool {
         vect.iter()
             .zip(other)
@@ -353,7 +1507,9 @@ mod tests {
 
     #[test]
     fn test_gen_alg_creation_init_pop() {
-        let init_pop: Vec<DummyGenetic> = (0..POP_SIZE).map(|_| DummyGenetic::generate()).collect();
+        let init_pop: Vec<DummyGenetic> = (0..POP_SIZE)
+            .map(|_| DummyGenetic::generate(&mut rand::rng()))
+            .collect();
 
         let gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, Some(&init_pop));
         let gen_vec = &gen_alg.current_population;
@@ -377,9 +1533,7 @@ mod tests {
 
         println!("{:?}", gen_alg.current_population);
 
-        let result = gen_alg
-            .run_genetic_algorithm(NUM_GENS, 0.5, 0.05, 0)
-            .unwrap();
+        let result = run(&mut gen_alg, NUM_GENS, 0.5, 0.05, 0).unwrap();
         let gen_vec = &gen_alg.current_population;
 
         println!("{:?}", result);
@@ -392,27 +1546,65 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn test_run_genetic_algorithm_selection_rate_low() {
         let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None);
 
         println!("{:?}", gen_alg.current_population);
 
-        gen_alg
-            .run_genetic_algorithm(NUM_GENS, 0.001, 0.0, 0)
-            .unwrap();
+        let error = run(&mut gen_alg, NUM_GENS, 0.001, 0.0, 0).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Config(ConfigError::TooFewSelected {
+                selected_count: 0,
+                parent_count: 2
+            })
+        ));
+        assert_eq!(gen_alg.current_generation, 0);
     }
 
     #[test]
-    #[should_panic]
     fn test_run_genetic_algorithm_selection_rate_not_in_range() {
         let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None);
 
         println!("{:?}", gen_alg.current_population);
 
-        gen_alg
-            .run_genetic_algorithm(NUM_GENS, 2.0, 0.0, 0)
+        let error = run(&mut gen_alg, NUM_GENS, 2.0, 0.0, 0).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Config(ConfigError::RateOutOfRange {
+                name: "selection_rate",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_elite_count_too_large() {
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None);
+
+        assert!(run(&mut gen_alg, NUM_GENS, 0.5, 0.0, POP_SIZE + 1).is_err());
+        assert!(gen_alg.population_history().is_empty());
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_config() {
+        let config = GenAlgConfig::builder()
+            .with_generations(20)
+            .with_crossover_rate(0.5)
+            .with_parent_count(3)
+            .with_history(HistoryRetention::None)
+            .with_threads(2)
+            .build()
             .unwrap();
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None).with_config(config);
+
+        gen_alg.run_genetic_algorithm().unwrap();
+
+        assert_eq!(gen_alg.current_generation, 20);
+        assert_eq!(gen_alg.current_population.len(), POP_SIZE);
+        assert!(gen_alg.population_history().is_empty());
+        assert!(gen_alg.generation_stats().is_empty());
+        assert!(are_vals_in_range(&gen_alg.current_population));
     }
 
     #[test]
@@ -421,24 +1613,12 @@ mod tests {
 
         println!("{:?}", gen_alg.current_population);
 
-        gen_alg
-            .run_genetic_algorithm(NUM_GENS, 0.1, 0.0, 0)
-            .unwrap();
-        gen_alg
-            .run_genetic_algorithm(NUM_GENS, 0.2, 0.0, 0)
-            .unwrap();
-        gen_alg
-            .run_genetic_algorithm(NUM_GENS, 0.5, 0.0, 0)
-            .unwrap();
-        gen_alg
-            .run_genetic_algorithm(NUM_GENS, 0.8, 0.0, 0)
-            .unwrap();
-        gen_alg
-            .run_genetic_algorithm(NUM_GENS, 0.9, 0.0, 0)
-            .unwrap();
-        gen_alg
-            .run_genetic_algorithm(NUM_GENS, 1.0, 0.0, 0)
-            .unwrap();
+        run(&mut gen_alg, NUM_GENS, 0.1, 0.0, 0).unwrap();
+        run(&mut gen_alg, NUM_GENS, 0.2, 0.0, 0).unwrap();
+        run(&mut gen_alg, NUM_GENS, 0.5, 0.0, 0).unwrap();
+        run(&mut gen_alg, NUM_GENS, 0.8, 0.0, 0).unwrap();
+        run(&mut gen_alg, NUM_GENS, 0.9, 0.0, 0).unwrap();
+        run(&mut gen_alg, NUM_GENS, 1.0, 0.0, 0).unwrap();
     }
 
     #[test]
@@ -448,14 +1628,14 @@ mod tests {
 
         println!("{:?}", gen_alg.current_population);
 
-        gen_alg
-            .run_genetic_algorithm(
-                FITNESS_TEST_NUM_GENS,
-                FITNESS_TEST_SELECTION_RATE,
-                FITNESS_TEST_MUTATION_RATE,
-                FITNESS_TEST_ELITE_COUNT,
-            )
-            .unwrap();
+        run(
+            &mut gen_alg,
+            FITNESS_TEST_NUM_GENS,
+            FITNESS_TEST_SELECTION_RATE,
+            FITNESS_TEST_MUTATION_RATE,
+            FITNESS_TEST_ELITE_COUNT,
+        )
+        .unwrap();
 
         println!("{:?}", gen_alg.current_population);
 
@@ -477,13 +1657,13 @@ mod tests {
         let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None);
 
         println!("{:?}", gen_alg.current_population);
-        gen_alg.run_genetic_algorithm(1, 0.5, 0.0, 0).unwrap();
+        run(&mut gen_alg, 1, 0.5, 0.0, 0).unwrap();
         let second_pop = gen_alg.current_population.clone();
-        gen_alg.run_genetic_algorithm(1, 0.5, 0.0, 0).unwrap();
+        run(&mut gen_alg, 1, 0.5, 0.0, 0).unwrap();
         let third_pop = gen_alg.current_population.clone();
-        gen_alg.run_genetic_algorithm(1, 0.5, 0.0, 0).unwrap();
+        run(&mut gen_alg, 1, 0.5, 0.0, 0).unwrap();
         let fourth_pop = gen_alg.current_population.clone();
-        gen_alg.run_genetic_algorithm(1, 0.5, 0.0, 0).unwrap();
+        run(&mut gen_alg, 1, 0.5, 0.0, 0).unwrap();
 
         assert!(are_populations_same(
             &second_pop,
@@ -499,6 +1679,435 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_history_retention() {
+        let run_with = |history| {
+            let config = GenAlgConfig::builder()
+                .with_generations(10)
+                .with_history(history)
+                .with_seed(7)
+                .build()
+                .unwrap();
+            let mut gen_alg = GenAlg::<DummyGenetic>::from_config(POP_SIZE, None, config);
+            gen_alg.run_genetic_algorithm().unwrap();
+            gen_alg
+        };
+
+        // seeded runs have the same populations, only what is kept differs
+        let full = run_with(HistoryRetention::Full);
+        assert_eq!(full.population_history().len(), 10);
+        assert_eq!(full.generation_stats().len(), 10);
+
+        let last = run_with(HistoryRetention::LastGenerations(3));
+        assert_eq!(last.population_history().len(), 3);
+        assert!(
+            last.population_history()
+                .iter()
+                .zip(&full.population_history()[7..])
+                .all(|(a, b)| are_populations_same(a, b))
+        );
+        let fitness = |gen_alg: &GenAlg<DummyGenetic>| {
+            gen_alg
+                .generation_stats()
+                .iter()
+                .map(|s| (s.max_fitness, s.mean_fitness))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fitness(&last), fitness(&full));
+
+        let best = run_with(HistoryRetention::BestOnly);
+        assert_eq!(best.population_history().len(), 10);
+        assert!(
+            best.population_history()
+                .iter()
+                .zip(full.population_history())
+                .all(|(a, b)| a.len() == 1 && are_populations_same(a, &b[..1]))
+        );
+
+        let stats = run_with(HistoryRetention::StatsOnly);
+        assert!(stats.population_history().is_empty());
+        assert_eq!(stats.generation_stats().len(), 10);
+
+        let none = run_with(HistoryRetention::None);
+        assert!(none.population_history().is_empty());
+        assert!(none.generation_stats().is_empty());
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_selection_strategies() {
+        use crate::selection::*;
+
+        let strategies: Vec<Box<dyn Selection>> = vec![
+            Box::new(Truncation),
+            Box::new(Tournament::new(4).unwrap()),
+            Box::new(Roulette),
+            Box::new(LinearRank::default()),
+            Box::new(ExponentialRank::default()),
+            Box::new(StochasticUniversalSampling),
+        ];
+
+        for strategy in strategies {
+            let name = format!("{:?}", strategy);
+            let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None);
+            gen_alg.selection = strategy;
+            let starting_fitness = gen_alg.get_total_fitness();
+
+            run(&mut gen_alg, NUM_GENS, 0.5, 0.05, 2).unwrap();
+
+            assert_eq!(gen_alg.current_population.len(), POP_SIZE, "{}", name);
+            assert!(are_vals_in_range(&gen_alg.current_population), "{}", name);
+            assert!(
+                gen_alg.get_total_fitness() > starting_fitness,
+                "{} did not improve the population",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_elite_survive_selection() {
+        let mut gen_alg =
+            GenAlg::<DummyGenetic>::new(POP_SIZE, None).with_selection(crate::selection::Roulette);
+        gen_alg
+            .current_population
+            .sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+
+        let survivors = gen_alg.select_survivors(10, 3, &mut rand::rng());
+
+        assert_eq!(survivors.len(), 10);
+        assert!(are_populations_same(
+            &survivors[..3],
+            &gen_alg.current_population[..3]
+        ));
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_generations_stop() {
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None);
+
+        let result = run(&mut gen_alg, 7, 0.5, 0.05, 0).unwrap();
+
+        assert_eq!(result.generations, 7);
+        assert!(matches!(result.stop_reason, StopCondition::Generations(7)));
+        assert_eq!(gen_alg.evaluations(), POP_SIZE + result.evaluations);
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_target_fitness() {
+        // Equal genes give the maximum fitness of 1000
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None)
+            .with_stop_condition(StopCondition::TargetFitness(1000.0));
+
+        let result = run(&mut gen_alg, 100_000, 0.5, 0.2, 0).unwrap();
+
+        assert!(matches!(
+            result.stop_reason,
+            StopCondition::TargetFitness(_)
+        ));
+        assert_eq!(result.best.fitness(), 1000.0);
+        assert_eq!(gen_alg.current_generation, result.generations);
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_stagnation() {
+        let init_pop = vec![DummyGenetic { a: 5, b: 5 }; POP_SIZE];
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, Some(&init_pop))
+            .with_stop_condition(StopCondition::Stagnation(5));
+
+        // The initial population is already optimal, so it never improves
+        let result = run(&mut gen_alg, NUM_GENS, 0.5, 0.05, 0).unwrap();
+
+        assert_eq!(result.generations, 5);
+        assert!(matches!(result.stop_reason, StopCondition::Stagnation(5)));
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_diversity_collapse() {
+        let init_pop = vec![DummyGenetic { a: 5, b: 7 }; POP_SIZE];
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, Some(&init_pop))
+            .with_stop_condition(StopCondition::DiversityBelow(0.5));
+
+        let result = run(&mut gen_alg, NUM_GENS, 0.5, 0.05, 0).unwrap();
+
+        assert_eq!(result.generations, 0);
+        assert!(matches!(
+            result.stop_reason,
+            StopCondition::DiversityBelow(_)
+        ));
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_evaluations_and_predicate() {
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None)
+            .with_stop_condition(StopCondition::Evaluations(120));
+
+        // Every generation evaluates the 50 new children
+        let result = run(&mut gen_alg, NUM_GENS, 0.5, 0.0, 0).unwrap();
+        assert_eq!(result.generations, 3);
+        assert_eq!(result.evaluations, 150);
+        assert!(matches!(
+            result.stop_reason,
+            StopCondition::Evaluations(120)
+        ));
+
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None)
+            .with_stop_condition(StopCondition::Evaluations(120))
+            .with_stop_condition(StopCondition::predicate(|state| state.generation == 1));
+
+        let result = run(&mut gen_alg, NUM_GENS, 0.5, 0.0, 0).unwrap();
+        assert_eq!(result.generations, 1);
+        assert!(matches!(result.stop_reason, StopCondition::Predicate(_)));
+    }
+
+    #[test]
+    fn test_run_genetic_algorithm_time_limit() {
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None)
+            .with_stop_condition(StopCondition::TimeLimit(Duration::ZERO));
+
+        let result = run(&mut gen_alg, NUM_GENS, 0.5, 0.05, 0).unwrap();
+
+        assert_eq!(result.generations, 0);
+        assert!(matches!(result.stop_reason, StopCondition::TimeLimit(_)));
+    }
+
+    #[test]
+    fn test_step() {
+        let config = GenAlgConfig::builder()
+            .with_mutation_rate(0.0)
+            .build()
+            .unwrap();
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None).with_config(config);
+
+        let first = gen_alg.step().unwrap();
+        let second_pop = gen_alg.current_population.clone();
+        let second = gen_alg.step().unwrap();
+
+        assert_eq!((first.generation, second.generation), (1, 2));
+        assert_eq!(first.evaluations, POP_SIZE / 2);
+        assert!(first.best_fitness <= second.best_fitness);
+        assert!(second.mean_fitness <= second.best_fitness);
+        assert_eq!(gen_alg.population_history().len(), 2);
+        assert!(are_populations_same(
+            &second_pop,
+            &gen_alg.population_history()[1]
+        ));
+    }
+
+    #[test]
+    fn test_step_invalid_config() {
+        let config = GenAlgConfig::builder()
+            .with_elite_count(POP_SIZE + 1)
+            .build()
+            .unwrap();
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None).with_config(config);
+
+        assert!(gen_alg.step().is_err());
+        assert!(gen_alg.generations().next().unwrap().is_err());
+        assert_eq!(gen_alg.current_generation, 0);
+    }
+
+    #[test]
+    fn test_generations_iterator() {
+        let config = GenAlgConfig::builder().with_generations(5).build().unwrap();
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None).with_config(config);
+
+        let mut generations = gen_alg.generations();
+        let summaries: Vec<GenerationSummary> = (&mut generations).map(Result::unwrap).collect();
+
+        assert_eq!(summaries.len(), 5);
+        assert!(
+            summaries
+                .iter()
+                .enumerate()
+                .all(|(i, summary)| summary.generation == i + 1)
+        );
+        assert!(matches!(
+            generations.stop_reason(),
+            Some(StopCondition::Generations(5))
+        ));
+
+        let result = generations.into_result().unwrap();
+        assert_eq!(result.generations, 5);
+        assert_eq!(
+            result.evaluations,
+            summaries.iter().map(|s| s.evaluations).sum::<usize>()
+        );
+
+        // a run which has not ended has no result
+        let mut generations = gen_alg.generations();
+        generations.next().unwrap().unwrap();
+        assert!(generations.stop_reason().is_none());
+        assert!(generations.into_result().is_none());
+        assert_eq!(gen_alg.current_generation, 6);
+    }
+
+    #[test]
+    fn test_seeded_runs_are_identical() {
+        let seeded_run = |threads| {
+            let config = GenAlgConfig::builder()
+                .with_generations(20)
+                .with_mutation_rate(0.2)
+                .with_elite_count(2)
+                .with_threads(threads)
+                .with_seed(42)
+                .build()
+                .unwrap();
+            let mut gen_alg = GenAlg::<DummyGenetic>::from_config(POP_SIZE, None, config)
+                .with_selection(crate::selection::Tournament::new(3).unwrap());
+            gen_alg.run_genetic_algorithm().unwrap();
+            gen_alg
+        };
+
+        let first = seeded_run(1);
+        let second = seeded_run(4);
+
+        assert_eq!(first.population_history().len(), 20);
+        assert!(
+            first
+                .population_history()
+                .iter()
+                .zip(second.population_history())
+                .all(|(a, b)| are_populations_same(a, b))
+        );
+        assert!(are_populations_same(
+            &first.current_population,
+            &second.current_population
+        ));
+    }
+
+    #[test]
+    fn test_fitness_cache() {
+        let cache = Arc::new(FitnessCache::new(1000));
+        let mut gen_alg =
+            GenAlg::<DummyGenetic>::new(POP_SIZE, None).with_fitness_cache(cache.clone());
+
+        let result = run(&mut gen_alg, NUM_GENS, 0.5, 0.05, 0).unwrap();
+        let stats = gen_alg.fitness_cache().unwrap().stats();
+
+        // the population converges, so children repeat
+        assert!(stats.hits > 0);
+        assert!(stats.len <= 1000);
+        assert_eq!(result.evaluations, stats.misses);
+        assert_eq!(cache.stats(), stats);
+    }
+
+    impl Distance for DummyGenetic {
+        fn distance(&self, other: &Self) -> f32 {
+            ((self.a - other.a).abs() + (self.b - other.b).abs()) as f32
+        }
+    }
+
+    #[test]
+    fn test_generation_stats() {
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None)
+            .with_fitness_cache(Arc::new(FitnessCache::new(1000)))
+            .with_distance_diversity();
+
+        let result = run(&mut gen_alg, 10, 0.5, 0.05, 0).unwrap();
+        let stats = gen_alg.generation_stats();
+
+        assert_eq!(stats.len(), 10);
+        assert_eq!(
+            stats.iter().map(|s| s.evaluations).sum::<usize>(),
+            result.evaluations
+        );
+        for (i, s) in stats.iter().enumerate() {
+            assert_eq!(s.generation, i + 1);
+            assert!(s.min_fitness <= s.median_fitness && s.median_fitness <= s.max_fitness);
+            assert!(s.min_fitness <= s.mean_fitness && s.mean_fitness <= s.max_fitness);
+            assert!(s.stddev_fitness >= 0.0);
+            assert!((1..=POP_SIZE).contains(&s.unique_genomes));
+            assert!(s.mean_distance.unwrap() >= 0.0);
+        }
+        assert_eq!(
+            stats[9].max_fitness,
+            gen_alg.current_population[0].fitness()
+        );
+        assert_eq!(
+            stats.iter().map(|s| s.cache_hits).sum::<usize>(),
+            gen_alg.fitness_cache().unwrap().stats().hits
+        );
+
+        // identical individuals have no distance
+        let init_pop = vec![DummyGenetic { a: 5, b: 5 }; POP_SIZE];
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, Some(&init_pop));
+        assert_eq!(gen_alg.mean_distance(DummyGenetic::distance), 0.0);
+        assert_eq!(gen_alg.unique_genomes(), 1);
+    }
+
+    /// Counts hook calls and stops the run at a generation
+    #[derive(Default)]
+    struct CountingObserver {
+        stop_at: usize,
+        calls: [usize; 4],
+    }
+
+    impl Observer<DummyGenetic> for CountingObserver {
+        fn on_start(&mut self, _view: &GenerationView<DummyGenetic>) -> ControlFlow<()> {
+            self.calls[0] += 1;
+            ControlFlow::Continue(())
+        }
+
+        fn on_evaluation(&mut self, view: &GenerationView<DummyGenetic>) -> ControlFlow<()> {
+            self.calls[1] += 1;
+            assert_eq!(view.population.len(), POP_SIZE);
+            ControlFlow::Continue(())
+        }
+
+        fn on_generation(&mut self, view: &GenerationView<DummyGenetic>) -> ControlFlow<()> {
+            self.calls[2] += 1;
+            assert!(view.best.fitness() >= view.population[0].fitness());
+            if view.generation == self.stop_at {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+
+        fn on_end(&mut self, view: &GenerationView<DummyGenetic>, stop_reason: &StopCondition) {
+            self.calls[3] += 1;
+            assert_eq!(view.generation, self.stop_at);
+            assert!(matches!(stop_reason, StopCondition::Requested));
+            assert_eq!(self.calls, [1, self.stop_at, self.stop_at, 1]);
+        }
+    }
+
+    #[test]
+    fn test_observer_requests_stop() {
+        let mut gen_alg =
+            GenAlg::<DummyGenetic>::new(POP_SIZE, None).with_observer(CountingObserver {
+                stop_at: 3,
+                ..Default::default()
+            });
+
+        let result = run(&mut gen_alg, NUM_GENS, 0.5, 0.05, 0).unwrap();
+
+        assert_eq!(result.generations, 3);
+        assert!(matches!(result.stop_reason, StopCondition::Requested));
+    }
+
+    #[test]
+    fn test_stats_collector() {
+        let stats = StatsCollector::new();
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None)
+            .with_observer(stats.clone())
+            .with_observer(ProgressPrinter::new().with_interval(5));
+
+        run(&mut gen_alg, 10, 0.5, 0.05, 0).unwrap();
+        let records = stats.records();
+
+        assert_eq!(records.len(), 11);
+        assert!(
+            records
+                .iter()
+                .enumerate()
+                .all(|(i, record)| record.generation == i
+                    && record.worst <= record.mean
+                    && record.mean <= record.best)
+        );
+        assert!(records.windows(2).all(|pair| pair[0].best <= pair[1].best));
+    }
+
     #[test]
     #[ignore]
     fn test_run_genetic_algorithm_dummy_fitness_speed_bulk() {
@@ -508,17 +2117,240 @@ mod tests {
         for _ in 0..SPEED_TEST_BULK_COUNT {
             let mut gen_alg = GenAlg::<DummyGenetic>::new(FITNESS_TEST_POP_SIZE, None);
 
-            gen_alg
-                .run_genetic_algorithm(
-                    FITNESS_TEST_NUM_GENS,
-                    FITNESS_TEST_SELECTION_RATE,
-                    FITNESS_TEST_MUTATION_RATE,
-                    FITNESS_TEST_ELITE_COUNT,
-                )
-                .unwrap();
+            run(
+                &mut gen_alg,
+                FITNESS_TEST_NUM_GENS,
+                FITNESS_TEST_SELECTION_RATE,
+                FITNESS_TEST_MUTATION_RATE,
+                FITNESS_TEST_ELITE_COUNT,
+            )
+            .unwrap();
         }
 
         let duration = start_timer.elapsed();
         println!("Time elapsed: {:?}", duration);
     }
+
+    #[test]
+    fn test_multi_objective() {
+        let config = GenAlgConfig::builder()
+            .with_generations(50)
+            .with_mutation_rate(0.2)
+            .with_seed(3)
+            .build()
+            .unwrap();
+        let mut gen_alg = GenAlg::<DummyGenetic>::from_config(POP_SIZE, None, config.clone());
+
+        let result = gen_alg.run_multi_objective().unwrap();
+
+        assert!(!result.front.is_empty());
+        assert_eq!(result.generations, 50);
+        assert_eq!(gen_alg.pareto_population().len(), POP_SIZE);
+        for a in &result.front {
+            assert_eq!(a.rank(), 0);
+            assert!(
+                result
+                    .front
+                    .iter()
+                    .all(|b| !pareto::dominates(b.objectives(), a.objectives()))
+            );
+        }
+        let mean_b_offset = result
+            .front
+            .iter()
+            .map(|indiv| (indiv.obj().b - 300).abs())
+            .sum::<i32>()
+            / result.front.len() as i32;
+        assert!(mean_b_offset < 50, "mean offset of b: {}", mean_b_offset);
+
+        let mut same_seed = GenAlg::<DummyGenetic>::from_config(POP_SIZE, None, config);
+        let same_front = same_seed.run_multi_objective().unwrap().front;
+        assert!(
+            result
+                .front
+                .iter()
+                .zip(&same_front)
+                .all(|(a, b)| a.obj() == b.obj())
+        );
+    }
+
+    #[test]
+    fn test_multi_objective_without_variation() {
+        let config = GenAlgConfig::builder()
+            .with_generations(10)
+            .with_crossover_rate(0.0)
+            .with_mutation_rate(0.0)
+            .with_seed(3)
+            .build()
+            .unwrap();
+        let population: Vec<DummyGenetic> = (0..10).map(|a| DummyGenetic { a, b: 0 }).collect();
+        let mut gen_alg = GenAlg::from_config(10, Some(&population), config);
+
+        gen_alg.run_multi_objective().unwrap();
+
+        // parents are not copied into the population as children, so it keeps all of them
+        let pareto_population = gen_alg.pareto_population();
+        assert_eq!(pareto_population.len(), 10);
+        assert!(
+            population
+                .iter()
+                .all(|obj| pareto_population.iter().any(|indiv| indiv.obj() == obj))
+        );
+    }
+
+    #[test]
+    fn test_receive_migrants() {
+        let config = GenAlgConfig::builder()
+            .with_elite_count(2)
+            .with_seed(4)
+            .build()
+            .unwrap();
+        let population: Vec<DummyGenetic> = (0..10).map(|a| DummyGenetic { a, b: 0 }).collect();
+        let mut gen_alg = GenAlg::from_config(10, Some(&population), config);
+        gen_alg.step().unwrap();
+        let elite = gen_alg.current_population()[..2].to_vec();
+
+        let migrant = DummyGenetic { a: 999, b: 0 };
+        let migrants = vec![FitnessIndiv::new(&migrant); 20];
+        gen_alg.receive_migrants(migrants, Replacement::Random, &mut task_rng(0, 0));
+
+        // everything but the elite was replaced, the population stays sorted
+        let population = gen_alg.current_population();
+        assert_eq!(population.len(), 10);
+        assert_eq!(population[9].obj(), &migrant);
+        assert_eq!(
+            population
+                .iter()
+                .filter(|indiv| indiv.obj() == &migrant)
+                .count(),
+            8
+        );
+        assert!(
+            elite
+                .iter()
+                .all(|e| population.iter().any(|p| p.obj() == e.obj()))
+        );
+
+        // fitness which is not a number is sorted instead of panicking
+        let nan = FitnessIndiv {
+            obj: migrant,
+            fitness: f32::NAN,
+        };
+        gen_alg.receive_migrants(vec![nan], Replacement::Worst, &mut task_rng(0, 1));
+        assert_eq!(gen_alg.current_population().len(), 10);
+    }
+
+    #[test]
+    fn test_steady_state() {
+        let run_with = |replacement| {
+            let config = GenAlgConfig::builder()
+                .with_generations(200)
+                .with_mutation_rate(0.3)
+                .with_elite_count(1)
+                .with_seed(9)
+                .with_mode(EvolutionMode::SteadyState {
+                    offspring: 4,
+                    replacement,
+                })
+                .build()
+                .unwrap();
+            let mut gen_alg = GenAlg::<DummyGenetic>::from_config(POP_SIZE, None, config);
+            let start_fitness = gen_alg.get_total_fitness();
+            let result = gen_alg.run_genetic_algorithm().unwrap();
+            (gen_alg, start_fitness, result)
+        };
+
+        for replacement in [
+            SteadyStateReplacement::Worst,
+            SteadyStateReplacement::TournamentLoser(3),
+        ] {
+            let (gen_alg, start_fitness, result) = run_with(replacement);
+
+            // at most one evaluation for every child
+            assert!(result.evaluations <= 200 * 4);
+            assert!(gen_alg.get_total_fitness() > start_fitness);
+            assert_eq!(gen_alg.generation_stats().len(), 200);
+            assert!(
+                gen_alg
+                    .population_history()
+                    .windows(2)
+                    .all(|w| w[1][0].fitness() >= w[0][0].fitness())
+            );
+
+            let (same_seed, _, _) = run_with(replacement);
+            assert!(are_populations_same(
+                &gen_alg.current_population,
+                &same_seed.current_population
+            ));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_checkpoint_resume() {
+        let path = std::env::temp_dir().join(format!("gen-alg-{}.checkpoint", std::process::id()));
+        let config = GenAlgConfig::builder()
+            .with_generations(20)
+            .with_history(HistoryRetention::StatsOnly)
+            .with_seed(11)
+            .build()
+            .unwrap();
+
+        let mut uninterrupted = GenAlg::<DummyGenetic>::from_config(POP_SIZE, None, config.clone());
+        let expected = uninterrupted.run_genetic_algorithm().unwrap();
+
+        // stopped between two checkpoints, as if the process was killed
+        let mut interrupted = GenAlg::<DummyGenetic>::from_config(POP_SIZE, None, config.clone())
+            .with_checkpoints(&path, 5)
+            .unwrap();
+        for summary in interrupted.generations().take(12) {
+            summary.unwrap();
+        }
+
+        let mut resumed = GenAlg::<DummyGenetic>::resume(&path, std::convert::identity).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(resumed.current_generation, 10);
+
+        let result = resumed.run_genetic_algorithm().unwrap();
+        assert_eq!(result.generations, 20);
+        assert_eq!(result.evaluations, expected.evaluations);
+        assert_eq!(result.best.obj(), expected.best.obj());
+        assert!(are_populations_same(
+            &resumed.current_population,
+            &uninterrupted.current_population
+        ));
+        assert_eq!(resumed.generation_stats().len(), 20);
+
+        assert!(matches!(
+            GenAlg::<DummyGenetic>::resume(&path, std::convert::identity),
+            Err(Error::Checkpoint(_))
+        ));
+        assert!(matches!(
+            GenAlg::<DummyGenetic>::from_config(POP_SIZE, None, config).with_checkpoints(&path, 0),
+            Err(ConfigError::ZeroCheckpointInterval)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_checkpoint_keeps_counters() {
+        let mut gen_alg = GenAlg::<DummyGenetic>::new(POP_SIZE, None)
+            .with_fitness_cache(Arc::new(FitnessCache::new(1000)));
+        run(&mut gen_alg, NUM_GENS, 0.5, 0.05, 0).unwrap();
+        gen_alg
+            .counters
+            .evaluation_errors
+            .store(3, Ordering::Relaxed);
+        let cache_hits = gen_alg.counters.cache_hits.load(Ordering::Relaxed);
+        assert!(cache_hits > 0);
+
+        let resumed = GenAlg::from_checkpoint(gen_alg.checkpoint(), std::convert::identity);
+
+        assert_eq!(resumed.evaluations(), gen_alg.evaluations());
+        assert_eq!(resumed.evaluation_errors(), 3);
+        assert_eq!(
+            resumed.counters.cache_hits.load(Ordering::Relaxed),
+            cache_hits
+        );
+    }
 }
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::Individual;
+use super::emu::{self, EmulatorResult};
+use super::machine::Machine;
+use super::pipeline::PipelineConfig;
+use crate::error::EmulatorError;
+
+/// Emulator used to evaluate DLX individuals.
+///
+/// Backends are shared between individuals and called from many threads at once.
+pub trait EmulatorBackend: fmt::Debug + Send + Sync {
+    /// Runs the program, starting from the given memory image.
+    ///
+    /// Programs which fault or run too long give a failed result, errors are reserved for
+    /// emulators which could not run the program at all.
+    fn run(&self, program: &Individual, memory: &[u32]) -> Result<EmulatorResult, EmulatorError>;
+
+    /// Hash of the kind and configuration of the backend, used in the fitness cache key.
+    ///
+    /// Backends with the same key give the same results. Individuals evaluated by backends
+    /// returning `None` are not cached.
+    fn cache_key(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Hashes a value together with the name of the backend kind
+pub(crate) fn backend_key(kind: &str, config: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    kind.hash(&mut hasher);
+    config.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// In-process DLX machine.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativeBackend {
+    pipeline: PipelineConfig,
+}
+
+impl NativeBackend {
+    /// Creates a native backend with the reference pipeline configuration
+    pub fn new() -> Self {
+        NativeBackend::default()
+    }
+
+    /// Creates a native backend with a custom pipeline configuration
+    pub fn with_pipeline(pipeline: PipelineConfig) -> Self {
+        NativeBackend { pipeline }
+    }
+}
+
+impl EmulatorBackend for NativeBackend {
+    fn run(&self, program: &Individual, memory: &[u32]) -> Result<EmulatorResult, EmulatorError> {
+        let mut machine = Machine::with_pipeline(memory, self.pipeline);
+        Ok(emu::collect_result(&mut machine, program))
+    }
+
+    fn cache_key(&self) -> Option<u64> {
+        Some(backend_key("native", &self.pipeline))
+    }
+}
+
+/// Python reference emulator from the `src/emulator` submodule, started once per evaluation.
+///
+/// The reference emulator loads its own memory image, so runs on any other image fail
+/// with `EmulatorError::UnsupportedMemory`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PythonBackend;
+
+impl EmulatorBackend for PythonBackend {
+    fn run(&self, program: &Individual, memory: &[u32]) -> Result<EmulatorResult, EmulatorError> {
+        if memory != emu::reference_memory() {
+            return Err(EmulatorError::UnsupportedMemory);
+        }
+
+        emu::run_python_emulator(program.to_string(), program.task().cycle_budget())
+    }
+
+    fn cache_key(&self) -> Option<u64> {
+        Some(backend_key("python", &()))
+    }
+}
+
+/// Backend returning prepared results instead of running programs, for tests.
+///
+/// Programs are matched by their assembly text. Unknown programs get the default result.
+#[derive(Debug)]
+pub struct ScriptedBackend {
+    results: HashMap<String, EmulatorResult>,
+    default: EmulatorResult,
+    calls: AtomicUsize,
+}
+
+impl ScriptedBackend {
+    /// Creates a backend which returns `default` for every program
+    pub fn new(default: EmulatorResult) -> Self {
+        ScriptedBackend {
+            results: HashMap::new(),
+            default,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Sets the result returned for the given program
+    pub fn with_result(mut self, program: &Individual, result: EmulatorResult) -> Self {
+        self.results.insert(program.to_string(), result);
+        self
+    }
+
+    /// Returns how many times the backend was run
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+impl EmulatorBackend for ScriptedBackend {
+    fn run(&self, program: &Individual, _memory: &[u32]) -> Result<EmulatorResult, EmulatorError> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+
+        Ok(self
+            .results
+            .get(&program.to_string())
+            .unwrap_or(&self.default)
+            .clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOI_CODE: &str = include_str!("../../../examples/short_code.txt");
+
+    fn result(cycle_count: usize) -> EmulatorResult {
+        EmulatorResult {
+            success: true,
+            cycle_count,
+            unknown: false,
+            memory: Some(vec![1, 2, 3]),
+        }
+    }
+
+    #[test]
+    fn test_native_backend() {
+        let indiv = Individual::new(SOI_CODE).unwrap();
+        let backend_result = NativeBackend::new()
+            .run(&indiv, &emu::reference_memory())
+            .unwrap();
+        let emu_result = emu::run_native_emulator(&indiv);
+
+        assert!(backend_result.success);
+        assert_eq!(backend_result.cycle_count, emu_result.cycle_count);
+        assert_eq!(backend_result.memory, emu_result.memory);
+    }
+
+    #[test]
+    fn test_native_backend_memory() {
+        let indiv = Individual::new("LDW R1, 0x00000004(R0)\nSTW R1, 0x00000000(R0)").unwrap();
+        let result = NativeBackend::new().run(&indiv, &[5, 6]).unwrap();
+
+        assert_eq!(result.memory.unwrap()[..2], [6, 6]);
+    }
+
+    #[test]
+    fn test_python_backend_memory() {
+        let indiv = Individual::new(SOI_CODE).unwrap();
+        let mut memory = emu::reference_memory();
+        memory[0] = 1;
+
+        assert!(matches!(
+            PythonBackend.run(&indiv, &memory),
+            Err(EmulatorError::UnsupportedMemory)
+        ));
+    }
+
+    #[test]
+    fn test_scripted_backend() {
+        let indiv = Individual::new("ADD R1, R2, R3").unwrap();
+        let other = Individual::new("SUB R1, R2, R3").unwrap();
+        let backend = ScriptedBackend::new(result(100)).with_result(&indiv, result(50));
+
+        assert_eq!(backend.run(&indiv, &[]).unwrap().cycle_count, 50);
+        assert_eq!(backend.run(&other, &[]).unwrap().cycle_count, 100);
+        assert_eq!(backend.calls(), 2);
+    }
+}
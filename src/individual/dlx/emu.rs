@@ -2,98 +2,191 @@ use std::process::Command;
 
 use cached::proc_macro::cached;
 use regex::Regex;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::machine::Fault;
+use super::pipeline::{PipelineConfig, PipelineReport};
+use super::{Individual, Machine};
+use crate::error::EmulatorError;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EmulatorResult {
     pub success: bool,
     pub cycle_count: usize,
-    unknown: bool,
+    pub unknown: bool,
     pub memory: Option<Vec<u32>>,
 }
 
-#[cached(size = 300)]
-pub fn run_python_emulator(code: String) -> EmulatorResult {
+const SOI_INPUT_ADDR: usize = 0x200 / 4;
+const SOI_INPUT_SIZE: usize = 32;
+const SOI_COEFFICIENTS_ADDR: usize = 0x2C0 / 4;
+const SOI_COEFFICIENTS_SIZE: usize = 16;
+
+/// Returns the memory image the reference emulator starts with.
+///
+/// Input samples `1..=32` are placed at 0x200 and filter coefficients (all `1`) at 0x2C0.
+pub fn reference_memory() -> Vec<u32> {
+    let mut memory = vec![0; super::machine::MEMORY_SIZE];
+
+    for i in 0..SOI_INPUT_SIZE {
+        memory[SOI_INPUT_ADDR + i] = i as u32 + 1;
+    }
+    for i in 0..SOI_COEFFICIENTS_SIZE {
+        memory[SOI_COEFFICIENTS_ADDR + i] = 1;
+    }
+
+    memory
+}
+
+/// Runs the individual on the in-process DLX machine, starting from `reference_memory()`.
+///
+/// Runs stop after the cycle budget of the individual's task, so they match
+/// `run_python_emulator` given the same budget, without spawning a process.
+pub fn run_native_emulator(indiv: &Individual) -> EmulatorResult {
+    let mut machine = Machine::new(&reference_memory());
+    collect_result(&mut machine, indiv)
+}
+
+/// Runs the individual on the machine and converts the outcome to an `EmulatorResult`
+pub(crate) fn collect_result(machine: &mut Machine, indiv: &Individual) -> EmulatorResult {
+    match machine.run(indiv) {
+        Ok(()) => EmulatorResult {
+            success: true,
+            cycle_count: machine.cycle_count(),
+            unknown: machine.unknown(),
+            memory: Some(machine.memory().to_vec()),
+        },
+        Err(_) => EmulatorResult {
+            success: false,
+            cycle_count: 0,
+            unknown: true,
+            memory: None,
+        },
+    }
+}
+
+/// Runs the program on the Python reference emulator from the `src/emulator` submodule,
+/// failing runs that go over `cycle_budget` cycles.
+///
+/// Only successful runs are cached, so a failed process start is retried on the next call.
+#[cached(size = 300, result = true)]
+pub fn run_python_emulator(
+    code: String,
+    cycle_budget: usize,
+) -> Result<EmulatorResult, EmulatorError> {
     let output = Command::new("python3")
-        .args(&["-B", "-u", "interface.py"])
-        .args(&["--instr", &code])
-        .args(&["--timeout", "18000"])
+        .args(["-B", "-u", "interface.py"])
+        .args(["--instr", &code])
+        .args(["--timeout", &cycle_budget.to_string()])
         .current_dir("src/emulator")
-        .output()
-        .expect("Failed to start python process");
+        .output()?;
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
         parse_emu_output(&stdout)
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        eprintln!("Python raised an error:\n{}", stderr);
-        EmulatorResult {
-            success: false,
-            cycle_count: 0,
-            unknown: false,
-            memory: None,
-        }
+        Err(EmulatorError::Failed(stderr.trim().to_string()))
     }
 }
 
+/// Runs the individual on the in-process DLX machine with the given pipeline configuration.
+///
+/// Returns the timing of every executed instruction, which explains where the cycles are spent.
+pub fn run_native_pipeline(
+    indiv: &Individual,
+    config: PipelineConfig,
+) -> Result<PipelineReport, Fault> {
+    let mut machine = Machine::with_pipeline(&reference_memory(), config);
+    machine.run(indiv)?;
+    Ok(machine.pipeline_report())
+}
+
 fn compress_spaces(s: &str) -> String {
     let re = Regex::new(r" +").unwrap();
     re.replace_all(s, " ").to_string()
 }
 
-fn parse_emu_output(output: &str) -> EmulatorResult {
+/// Formats a result the same way `interface.py` prints it, so it can be read back by `parse_emu_output`
+pub fn format_emu_output(result: &EmulatorResult) -> String {
+    if !result.success {
+        return "Failed".to_string();
+    }
+
+    let mut output = format!(
+        "Completed\n{}\n{}",
+        result.cycle_count,
+        if result.unknown { "True" } else { "False" }
+    );
+
+    if let Some(memory) = &result.memory {
+        let memory_hex: Vec<String> = memory.iter().map(|word| format!("{:08X}", word)).collect();
+        output.push('\n');
+        output.push_str(&memory_hex.join(" "));
+    }
+
+    output
+}
+
+pub(crate) fn parse_emu_output(output: &str) -> Result<EmulatorResult, EmulatorError> {
     let split_output: Vec<&str> = output.splitn(4, '\n').collect();
 
-    let success = split_output.get(0) == Some(&"Completed");
+    let success = split_output.first() == Some(&"Completed");
 
     if !success {
-        return EmulatorResult {
-            success: success,
+        return Ok(EmulatorResult {
+            success,
             cycle_count: 0,
             unknown: true,
             memory: None,
-        };
+        });
     }
 
-    let cycles: usize = split_output
-        .get(1)
-        .unwrap()
-        .trim()
-        .parse::<usize>()
-        .expect("Invalid cycle count format. Not a valid number.");
+    let cycle_str = split_output.get(1).unwrap_or(&"").trim();
+    let cycles: usize = cycle_str.parse::<usize>().map_err(|_| {
+        EmulatorError::InvalidOutput(format!("invalid cycle count '{}'", cycle_str))
+    })?;
 
     let unknown = split_output.get(2) == Some(&"True");
 
-    if split_output.get(3) == None {
-        return EmulatorResult {
-            success: success,
+    if split_output.get(3).is_none() {
+        return Ok(EmulatorResult {
+            success,
             cycle_count: cycles,
-            unknown: unknown,
+            unknown,
             memory: None,
-        };
+        });
     }
 
     let memory_string = compress_spaces(split_output[3]);
     let memory_hex = memory_string.split_terminator(&[' ', '\n'][..]);
 
     let memory = memory_hex
-        .map(|hex| u32::from_str_radix(hex, 16).unwrap())
-        .collect();
+        .map(|hex| {
+            u32::from_str_radix(hex, 16)
+                .map_err(|_| EmulatorError::InvalidOutput(format!("invalid memory word '{}'", hex)))
+        })
+        .collect::<Result<_, _>>()?;
 
-    EmulatorResult {
-        success: success,
+    Ok(EmulatorResult {
+        success,
         cycle_count: cycles,
-        unknown: unknown,
+        unknown,
         memory: Some(memory),
-    }
+    })
 }
 
 #[cfg(test)]
 mod test {
 
+    use std::sync::Arc;
+
     use rayon::prelude::*;
 
     use super::*;
+    use crate::individual::dlx::Task;
 
     const SOI_CODE: &str = "ADDI R0, 0x00000010, R12\nADDI R0, 0x00000020, R11\nAND  R1, R0, R1\nMULI R12, 0x00000004, R12\nAND  R4, R0, R4\nMULI R11, 0x00000004, R11\nSUBI R12, 0x00000004, R13\nLDW  R7, 0x00000200(R1)\nADD  R4, R0, R5\nSUBI R13, 0x00000004, R17\nl1: AND  R2, R0, R2\nAND  R3, R0, R3\nSTW  R7, 0x00000280(R4)\nLDW  R9, 0x00000280(R5)\nLDW  R10, 0x000002C0(R2)\nl2: SUB  R5, R17, R14\nADD  R3, R9, R3\nADDI R2, 0x00000004, R2\nBRLE R14, h1\nSUB  R2, R12, R15\nADDI R5, 0x00000004, R5\nAND  R5, R0, R5\nh1: MUL  R3, R10, R3\nBRNZ R15, l2\nLDW  R9, 0x00000280(R5)\nLDW  R10, 0x000002C0(R2)\nSTW  R3, 0x00000300(R1)\nSUBI R4, 0x00000004, R4\nADDI R1, 0x00000004, R1\nNOP  \nBRGE R4, h2\nSUB  R1, R11, R15\nNOP  \nADD  R13, R0, R4\nh2: NOP  \nBRNZ R15, l1\nLDW  R7, 0x00000200(R1)\nADD  R4, R0, R5";
     const RUN_COUNT: usize = 10;
@@ -112,8 +205,9 @@ mod test {
     ];
 
     #[test]
+    #[ignore = "requires the src/emulator submodule"]
     fn emu_test() {
-        let result = run_python_emulator(SOI_CODE.to_string());
+        let result = run_python_emulator(SOI_CODE.to_string(), Task::soi().cycle_budget()).unwrap();
         println!("{:?}", result);
 
         assert!(result.success);
@@ -126,11 +220,133 @@ mod test {
     }
 
     #[test]
+    #[ignore = "requires the src/emulator submodule"]
     fn emu_mt_test() {
         let codes: Vec<&str> = vec![SOI_CODE; RUN_COUNT];
 
         codes.par_iter().for_each(|code| {
-            run_python_emulator(code.to_string());
+            run_python_emulator(code.to_string(), Task::soi().cycle_budget()).unwrap();
         });
     }
+
+    #[test]
+    #[ignore = "requires the src/emulator submodule"]
+    fn native_matches_python_emu() {
+        let task = Task::soi();
+        let native = run_native_emulator(&Individual::new(SOI_CODE).unwrap());
+        let python = run_python_emulator(SOI_CODE.to_string(), task.cycle_budget()).unwrap();
+
+        assert_eq!(native.success, python.success);
+        assert_eq!(native.cycle_count, python.cycle_count);
+        assert_eq!(native.unknown, python.unknown);
+        assert_eq!(native.memory, python.memory);
+    }
+
+    #[test]
+    fn native_emu_test() {
+        let result = run_native_emulator(&Individual::new(SOI_CODE).unwrap());
+        println!("{:?}", result);
+
+        assert!(result.success);
+        assert_eq!(result.cycle_count, EXPECTED_CYCLES);
+        assert!(result.unknown);
+        assert_eq!(
+            &result.memory.as_ref().unwrap()[MEMORY_OUTPUT_ADDR..MEMORY_OUTPUT_ADDR_END],
+            &EXPECTED_MEMORY
+        );
+    }
+
+    #[test]
+    fn native_emu_mt_test() {
+        let indivs = vec![Individual::new(SOI_CODE).unwrap(); RUN_COUNT];
+
+        let results: Vec<EmulatorResult> = indivs.par_iter().map(run_native_emulator).collect();
+
+        assert!(
+            results
+                .iter()
+                .all(|result| result.success && result.cycle_count == EXPECTED_CYCLES)
+        );
+    }
+
+    #[test]
+    fn native_emu_division_by_zero() {
+        let result = run_native_emulator(
+            &Individual::new("ADDI R0, 0x00000004, R1\nDIV R1, R0, R2").unwrap(),
+        );
+
+        assert!(!result.success);
+        assert!(result.memory.is_none());
+    }
+
+    #[test]
+    fn native_emu_invalid_memory_access() {
+        let result = run_native_emulator(&Individual::new("LDW R1, 0x00000002(R0)").unwrap());
+        assert!(!result.success);
+
+        let result = run_native_emulator(&Individual::new("STW R1, 0x00001000(R0)").unwrap());
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn native_emu_infinite_loop() {
+        let result = run_native_emulator(&Individual::new("l1: NOP\nBRZ R0, l1").unwrap());
+
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn native_emu_cycle_budget() {
+        // Counts down from 3200, taking a little over 19000 cycles
+        let code = "ADDI R0, 0x00000C80, R1\nl1: SUBI R1, 0x00000001, R1\nBRNZ R1, l1";
+        let with_budget = |budget| {
+            Individual::new(code)
+                .unwrap()
+                .with_task(Arc::new(Task::new("count").with_cycle_budget(budget)))
+        };
+
+        let result = run_native_emulator(&with_budget(25000));
+        assert!(result.success);
+        assert!(result.cycle_count > 18000);
+        // Fits the default budget of 20000 cycles
+        assert!(run_native_emulator(&Individual::new(code).unwrap()).success);
+
+        let mut machine = Machine::new(&reference_memory());
+        let indiv = with_budget(result.cycle_count - 1);
+        assert_eq!(
+            machine.run(&indiv),
+            Err(Fault::CycleLimitExceeded(result.cycle_count - 1))
+        );
+        assert!(run_native_emulator(&with_budget(result.cycle_count)).success);
+    }
+
+    #[test]
+    fn emu_output_roundtrip() {
+        let result = run_native_emulator(&Individual::new(SOI_CODE).unwrap());
+        let parsed = parse_emu_output(&format_emu_output(&result)).unwrap();
+
+        assert!(parsed.success);
+        assert_eq!(parsed.cycle_count, result.cycle_count);
+        assert_eq!(parsed.unknown, result.unknown);
+        assert_eq!(parsed.memory, result.memory);
+
+        let failed = run_native_emulator(&Individual::new("l1: NOP\nBRZ R0, l1").unwrap());
+        assert!(
+            !parse_emu_output(&format_emu_output(&failed))
+                .unwrap()
+                .success
+        );
+    }
+
+    #[test]
+    fn emu_output_invalid() {
+        assert!(matches!(
+            parse_emu_output("Completed\nmany\nFalse"),
+            Err(EmulatorError::InvalidOutput(_))
+        ));
+        assert!(matches!(
+            parse_emu_output("Completed\n42\nFalse\n00000001 XYZ"),
+            Err(EmulatorError::InvalidOutput(_))
+        ));
+    }
 }
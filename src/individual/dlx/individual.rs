@@ -1,78 +1,137 @@
 use std::cmp;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::iter;
+use std::ops::Range;
+use std::ptr;
+use std::str::FromStr;
+use std::sync::Arc;
 
 use rand::{
-    Rng,
+    Rng, RngCore,
     distr::{Distribution, weighted::WeightedIndex},
-    random,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-use crate::individual::{dlx, genetic::Genetic};
+use crate::error::{Error, ParseError, ParseErrorKind};
+use crate::individual::{
+    dlx,
+    genetic::{Distance, Genetic, MultiObjective},
+};
 
-use super::emu;
+use super::backend::{EmulatorBackend, NativeBackend};
 use super::opcode::BRANCH_OPCODES;
+use super::section::{SectionKind, Sections};
+use super::task::Evaluation;
 use super::{
-    Opcode, Register,
+    Opcode, Register, Task,
     instruction::{MAX_IMMEDIATE_FOR_RAND, MAX_REGISTER_FOR_RAND},
 };
 
 const DLX_INDIV_MAX_SIZE: usize = 90;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct Label {
     name: String,
     location: usize,
 }
 
 /// Individual for the DLX algorithm
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// Each individual carries the task it solves and the emulator backend used to compute its fitness.
+/// Children created by crossover share the task and backend of their first parent.
+///
+/// Serialization keeps only the program, deserialized individuals solve the SOI task
+/// with the native backend until they are given another one. Resumed runs give them back
+/// through the `rebind` function of `GenAlg::resume`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Individual {
     instructions: Vec<dlx::Instruction>,
     labels: Vec<Label>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "Task::soi"))]
+    task: Arc<Task>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "native_backend"))]
+    backend: Arc<dyn EmulatorBackend>,
+}
+
+impl PartialEq for Individual {
+    fn eq(&self, other: &Self) -> bool {
+        self.instructions == other.instructions && self.labels == other.labels
+    }
 }
 
-unsafe impl Sync for Individual {}
-unsafe impl Send for Individual {}
+impl Eq for Individual {}
 
 impl Individual {
+    /// Replaces the emulator backend used for fitness evaluation
+    pub fn with_backend(mut self, backend: Arc<dyn EmulatorBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Getter for emulator backend
+    pub fn backend(&self) -> &Arc<dyn EmulatorBackend> {
+        &self.backend
+    }
+
+    /// Replaces the task the individual is evaluated on
+    pub fn with_task(mut self, task: Arc<Task>) -> Self {
+        self.task = task;
+        self
+    }
+
+    /// Getter for task
+    pub fn task(&self) -> &Arc<Task> {
+        &self.task
+    }
+
+    /// Runs the program on every test vector of its task and reports which ones it failed
+    pub fn evaluate(&self) -> Result<Evaluation, Error> {
+        let results = self
+            .task
+            .vector_memories()
+            .iter()
+            .map(|memory| self.backend.run(self, memory))
+            .collect::<Result<_, _>>()?;
+
+        Ok(self.task.evaluate(results)?)
+    }
+
     pub fn get_label_position(&self, name: &str) -> Option<usize> {
         let label = self.labels.iter().find(|l| l.name == name);
-        match label {
-            Some(label) => Some(label.location),
-            None => None,
-        }
+        label.map(|label| label.location)
     }
 
-    fn last_nop_index(&self) -> usize {
-        // let index = self
-        //     .instructions
-        //     .iter()
-        //     .position(|a| a.get_opcode() == &Opcode::NOP);
-
-        // match index {
-        //     Some(n) => n,
-        //     None => DLX_INDIV_MAX_SIZE,
-        // }
+    /// Getter for instructions
+    pub fn instructions(&self) -> &[dlx::Instruction] {
+        &self.instructions
+    }
 
+    /// Returns the index of the first NOP of the trailing NOP padding,
+    /// which is also the length of the program without the padding.
+    pub(crate) fn last_nop_index(&self) -> usize {
         self.instructions
             .iter()
             .rposition(|a| a.get_opcode() != &Opcode::NOP)
-            .unwrap_or(0)
+            .map_or(0, |index| index + 1)
     }
 
-    fn duplicate_rand_instruction(&mut self) -> () {
+    fn duplicate_rand_instruction(&mut self, rng: &mut dyn RngCore) {
         let last_pos = cmp::min(self.last_nop_index(), DLX_INDIV_MAX_SIZE - 1);
 
         if last_pos == 0 {
             return;
         }
 
-        let mut rng = rand::rng();
         let position = rng.random_range(0..last_pos);
 
         let instr = self.instructions[position].clone();
 
-        if BRANCH_OPCODES.contains(&instr.get_opcode()) {
+        if BRANCH_OPCODES.contains(instr.get_opcode()) {
             return;
         }
 
@@ -86,20 +145,19 @@ impl Individual {
         self.instructions.insert(position, instr);
     }
 
-    fn move_rand_instruction(&mut self) -> () {
+    fn move_rand_instruction(&mut self, rng: &mut dyn RngCore) {
         let last_pos = cmp::min(self.last_nop_index(), DLX_INDIV_MAX_SIZE - 1);
 
         if last_pos == 0 {
             return;
         }
 
-        let mut rng = rand::rng();
         let position = rng.random_range(0..last_pos);
         let position2 = rng.random_range(0..last_pos);
 
         let instr = self.instructions[position].clone();
 
-        if BRANCH_OPCODES.contains(&instr.get_opcode()) {
+        if BRANCH_OPCODES.contains(instr.get_opcode()) {
             return;
         }
 
@@ -121,14 +179,13 @@ impl Individual {
         self.instructions.insert(position2, instr);
     }
 
-    fn remove_rand_instruction(&mut self) -> () {
+    fn remove_rand_instruction(&mut self, rng: &mut dyn RngCore) {
         let last_pos = cmp::min(self.last_nop_index(), DLX_INDIV_MAX_SIZE - 1);
 
         if last_pos == 0 {
             return;
         }
 
-        let mut rng = rand::rng();
         let mut position: usize = rng.random_range(0..last_pos);
 
         for i in 0..=5 {
@@ -139,7 +196,7 @@ impl Individual {
             position = rng.random_range(0..last_pos);
             let instr = &self.instructions[position];
 
-            if BRANCH_OPCODES.contains(&instr.get_opcode()) {
+            if BRANCH_OPCODES.contains(instr.get_opcode()) {
                 continue;
             }
 
@@ -159,18 +216,16 @@ impl Individual {
         self.instructions.push(dlx::Instruction::default());
     }
 
-    fn add_rand_instruction(&mut self) -> () {
+    fn add_rand_instruction(&mut self, rng: &mut dyn RngCore) {
         let last_pos = self.last_nop_index();
 
         if last_pos >= DLX_INDIV_MAX_SIZE {
             return;
         }
 
-        let mut rng = rand::rng();
-
         let position = rng.random_range(0..=last_pos);
 
-        let instr = dlx::Instruction::get_rand();
+        let instr = dlx::Instruction::get_rand(rng);
 
         // Make labels with location higher than position, higher by 1
         for label in self.labels.iter_mut() {
@@ -183,7 +238,7 @@ impl Individual {
         self.instructions.pop();
     }
 
-    fn change_rand_instruction(&mut self) -> () {
+    fn change_rand_instruction(&mut self, rng: &mut dyn RngCore) {
         let last_pos = cmp::min(self.last_nop_index(), DLX_INDIV_MAX_SIZE - 1);
 
         // Do nothing if only NOPs instructions
@@ -191,15 +246,14 @@ impl Individual {
             return;
         }
 
-        let mut rng = rand::rng();
         let position = rng.random_range(0..last_pos);
 
-        let instr = dlx::Instruction::get_rand();
+        let instr = dlx::Instruction::get_rand(rng);
 
         self.instructions[position] = instr;
     }
 
-    fn change_operands(&mut self) -> () {
+    fn change_operands(&mut self, rng: &mut dyn RngCore) {
         let last_pos = cmp::min(self.last_nop_index(), DLX_INDIV_MAX_SIZE - 1);
 
         // Do nothing if only NOPs instructions
@@ -207,115 +261,308 @@ impl Individual {
             return;
         }
 
-        let mut rng = rand::rng();
         let rand_index = rng.random_range(0..last_pos);
 
         let instr_type = self.instructions[rand_index].get_opcode().get_type();
 
         match instr_type {
-            dlx::opcode::OpcodeType::RType => self.rand_change_reg_in_instruction(rand_index),
+            dlx::opcode::OpcodeType::RType => self.rand_change_reg_in_instruction(rand_index, rng),
             dlx::opcode::OpcodeType::IType => {
-                if random::<bool>() {
-                    self.rand_change_reg_in_instruction(rand_index);
+                if rng.random::<bool>() {
+                    self.rand_change_reg_in_instruction(rand_index, rng);
                 } else {
-                    self.rand_change_imm_in_instruction(rand_index);
+                    self.rand_change_imm_in_instruction(rand_index, rng);
                 }
             }
             dlx::opcode::OpcodeType::JType => todo!(),
         }
     }
 
-    fn rand_change_reg_in_instruction(&mut self, index: usize) -> () {
-        let register = Register::rand_up_to(MAX_REGISTER_FOR_RAND).unwrap();
+    fn rand_change_reg_in_instruction(&mut self, index: usize, rng: &mut dyn RngCore) {
+        let register = Register::rand_up_to(MAX_REGISTER_FOR_RAND, rng).unwrap();
 
-        self.instructions[index].set_register(None, register);
+        self.instructions[index].set_register(None, register, rng);
     }
 
-    fn rand_change_imm_in_instruction(&mut self, index: usize) -> () {
-        let mut rng = rand::rng();
+    fn rand_change_imm_in_instruction(&mut self, index: usize, rng: &mut dyn RngCore) {
         let imm = rng.random_range(-MAX_IMMEDIATE_FOR_RAND..MAX_IMMEDIATE_FOR_RAND);
 
         self.instructions[index].set_immidiate(imm);
     }
 
-    // fn get_inner_loop_bounds(&self) -> (usize, usize) {
-    //     (0, self.instructions.len() - 1)
-    // }
-
-    // fn get_setup_part_bounds(&self) -> (usize, usize) {
-    //     (0, self.instructions.len() - 1)
-    // }
-
-    // fn get_outer_loop_top_part_bounds(&self) -> (usize, usize) {
-    //     (0, self.instructions.len() - 1)
-    // }
-
-    // fn get_outer_loop_bottom_part_bounds(&self) -> (usize, usize) {
-    //     (0, self.instructions.len() - 1)
-    // }
-}
+    /// Builds a child from parts of this program and its crossover partner, in order.
+    ///
+    /// Labels inside a part move with it. Labels inside the counterpart of a part are moved
+    /// to the same offset in the part, clamped to its end, so branches to them stay in the same
+    /// section. Labels ending up at the same instruction are merged. Branches to labels outside
+    /// of all parts are replaced with NOPs.
+    ///
+    /// Returns `None` if the child does not fit in `DLX_INDIV_MAX_SIZE` instructions.
+    pub(crate) fn splice(&self, parts: &[SplicePart]) -> Option<Individual> {
+        let child_len: usize = parts.iter().map(|part| part.range.len()).sum();
+        if child_len > DLX_INDIV_MAX_SIZE {
+            return None;
+        }
 
-#[rustfmt::skip]
-const EXPECTED_MEMORY: [u32; 32] = [
-    1, 3, 6, 10, 15, 21, 28, 36,
-    45, 55, 66, 78, 91, 105, 120, 136,
-    152, 168, 184, 200, 216, 232, 248, 264,
-    280, 296, 312, 328, 344, 360, 376, 392,
-];
+        // Parents in order of appearance, labels are told apart by parent index
+        let mut parents: Vec<&Individual> = Vec::new();
+        for part in parts {
+            let part_parents =
+                iter::once(part.source).chain(part.counterpart.as_ref().map(|c| c.0));
+            for indiv in part_parents {
+                if !parents.iter().any(|parent| ptr::eq(*parent, indiv)) {
+                    parents.push(indiv);
+                }
+            }
+        }
+        let parent_index = |indiv: &Individual| {
+            parents
+                .iter()
+                .position(|parent| ptr::eq(*parent, indiv))
+                .unwrap()
+        };
+
+        // Child location of every label, by parent index and label name
+        let mut locations: HashMap<(usize, &str), usize> = HashMap::new();
+        // Name of the label at every child location
+        let mut names: HashMap<usize, String> = HashMap::new();
+        let mut used_names: HashSet<String> = HashSet::new();
+
+        let mut offset = 0;
+        for (index, part) in parts.iter().enumerate() {
+            let is_last = index + 1 == parts.len();
+            let sources = iter::once((part.source, &part.range)).chain(
+                part.counterpart
+                    .as_ref()
+                    .map(|(indiv, range)| (*indiv, range)),
+            );
+
+            for (indiv, range) in sources {
+                for label in &indiv.labels {
+                    let key = (parent_index(indiv), label.name.as_str());
+                    let in_range =
+                        range.contains(&label.location) || (is_last && label.location == range.end);
+
+                    if !in_range || locations.contains_key(&key) {
+                        continue;
+                    }
+
+                    let location = offset + (label.location - range.start).min(part.range.len());
+                    locations.insert(key, location);
+
+                    names.entry(location).or_insert_with(|| {
+                        let mut name = label.name.clone();
+                        let mut suffix = 1;
+                        while used_names.contains(&name) {
+                            suffix += 1;
+                            name = format!("{}_{}", label.name, suffix);
+                        }
+                        used_names.insert(name.clone());
+                        name
+                    });
+                }
+            }
 
-const MEMORY_OUTPUT_ADDR: usize = 192;
-const MEMORY_OUTPUT_SIZE: usize = 32;
-const MEMORY_OUTPUT_ADDR_END: usize = MEMORY_OUTPUT_ADDR + MEMORY_OUTPUT_SIZE;
+            offset += part.range.len();
+        }
 
-impl Genetic for Individual {
-    fn fitness(&self) -> f32 {
-        let result = emu::run_python_emulator(self.to_string());
+        let mut instructions = Vec::with_capacity(DLX_INDIV_MAX_SIZE);
+        for part in parts {
+            let source = parent_index(part.source);
+            for instr in &part.source.instructions[part.range.clone()] {
+                let mut instr = instr.clone();
+
+                // The other parent may have a label with the same name, so branches to labels
+                // which were not copied must not keep theirs
+                if let Some(label) = instr.get_label() {
+                    match locations.get(&(source, label.as_str())) {
+                        Some(location) => instr.set_label(Some(names[location].clone())),
+                        None => instr = dlx::Instruction::default(),
+                    }
+                }
 
-        if !result.success {
-            return 0.0;
+                instructions.push(instr);
+            }
         }
+        instructions.resize(DLX_INDIV_MAX_SIZE, dlx::Instruction::default());
+
+        // Only labels which are branched to are kept
+        let mut labels: Vec<Label> = names
+            .into_iter()
+            .filter(|(_, name)| {
+                instructions
+                    .iter()
+                    .any(|instr| instr.get_label().as_deref() == Some(name.as_str()))
+            })
+            .map(|(location, name)| Label { name, location })
+            .collect();
+        labels.sort_by_key(|label| label.location);
+
+        let mut child = Individual {
+            instructions,
+            labels,
+            task: self.task.clone(),
+            backend: self.backend.clone(),
+        };
+        child.repair_labels();
+        Some(child)
+    }
 
-        if result.cycle_count > 20000 {
-            return 0.0;
+    /// Crossover which takes every section from a random parent, at least one from each.
+    ///
+    /// Returns `None` if either parent has no loops, or the child would be too long.
+    fn section_crossover(&self, other: &Individual, rng: &mut dyn RngCore) -> Option<Individual> {
+        let sections = Sections::find(self)?;
+        let other_sections = Sections::find(other)?;
+
+        let mut from_other: [bool; 4] = rng.random();
+        if from_other.iter().all(|&b| b == from_other[0]) {
+            let index = rng.random_range(0..from_other.len());
+            from_other[index] = !from_other[index];
         }
 
-        if &result.memory.as_ref().unwrap()[MEMORY_OUTPUT_ADDR..MEMORY_OUTPUT_ADDR_END]
-            != &EXPECTED_MEMORY
-        {
-            return 1.0;
-        }
+        let parts: Vec<SplicePart> = SectionKind::ALL
+            .iter()
+            .zip(from_other)
+            .map(|(&kind, from_other)| {
+                let own = (self, sections.range(kind));
+                let others = (other, other_sections.range(kind));
+                let (source, counterpart) = if from_other {
+                    (others, own)
+                } else {
+                    (own, others)
+                };
 
-        (20000 - result.cycle_count) as f32
-    }
+                SplicePart {
+                    source: source.0,
+                    range: source.1,
+                    counterpart: Some(counterpart),
+                }
+            })
+            .collect();
 
-    fn generate() -> Self {
-        Individual::new("NOP")
+        self.splice(&parts)
     }
 
-    fn crossover(&self, other: &Self) -> Self {
+    /// Crossover joining the first half of this program with the second half of the other
+    fn half_crossover(&self, other: &Individual) -> Individual {
         let mid_instr = self.last_nop_index() / 2;
         let mid_instr_other = other.last_nop_index() / 2;
 
-        let first_half = self.instructions.split_at(mid_instr).0;
-        let second_half = other.instructions.split_at(mid_instr_other).1;
+        let parts = [
+            SplicePart {
+                source: self,
+                range: 0..mid_instr,
+                counterpart: None,
+            },
+            SplicePart {
+                source: other,
+                range: mid_instr_other..other.last_nop_index(),
+                counterpart: None,
+            },
+        ];
 
-        let mut child: Vec<_> = first_half
-            .iter()
-            .chain(second_half.iter())
-            .cloned()
-            .collect();
+        self.splice(&parts).unwrap_or_else(|| self.clone())
+    }
 
-        if child.len() > DLX_INDIV_MAX_SIZE {
-            child.truncate(DLX_INDIV_MAX_SIZE);
+    /// Checks that every label is inside the program, label names are unique
+    /// and every branch to a label branches to an existing one.
+    pub fn validate_labels(&self) -> Result<(), String> {
+        for (index, label) in self.labels.iter().enumerate() {
+            if label.location >= self.instructions.len() {
+                return Err(format!(
+                    "label '{}' points to instruction {}, past the end of the program",
+                    label.name, label.location
+                ));
+            }
+            if self.labels[..index].iter().any(|l| l.name == label.name) {
+                return Err(format!("label '{}' is defined more than once", label.name));
+            }
         }
 
-        Individual {
-            instructions: child,
-            labels: self.labels.clone(),
+        for (index, instr) in self.instructions.iter().enumerate() {
+            if let Some(label) = instr.get_label()
+                && self.get_label_position(label).is_none()
+            {
+                return Err(format!(
+                    "branch at instruction {} points to missing label '{}'",
+                    index, label
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drops labels past the end of the program and replaces branches to missing labels with NOPs
+    fn repair_labels(&mut self) {
+        let len = self.instructions.len();
+        self.labels.retain(|label| label.location < len);
+
+        for index in 0..len {
+            if let Some(label) = self.instructions[index].get_label()
+                && self.get_label_position(label).is_none()
+            {
+                self.instructions[index] = dlx::Instruction::default();
+            }
         }
     }
+}
+
+/// Part of a parent program copied into a child by `Individual::splice`
+pub(crate) struct SplicePart<'a> {
+    pub source: &'a Individual,
+    pub range: Range<usize>,
+    /// Instructions of the other parent with the same role as this part
+    pub counterpart: Option<(&'a Individual, Range<usize>)>,
+}
+
+impl Genetic for Individual {
+    /// Fitness computed by the task, 0 if the program could not be evaluated
+    fn fitness(&self) -> f32 {
+        self.try_fitness().unwrap_or(0.0)
+    }
+
+    /// Fitness computed by the task, fails if the emulator could not run the program
+    /// or its results did not fit the task
+    fn try_fitness(&self) -> Result<f32, Error> {
+        Ok(self.evaluate()?.fitness)
+    }
+
+    /// Hash of the program, the task content and the backend configuration.
+    ///
+    /// `None` if the backend has no cache key.
+    fn cache_key(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        self.instructions.hash(&mut hasher);
+        self.labels.hash(&mut hasher);
+        self.task.hash(&mut hasher);
+        self.backend.cache_key()?.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    /// Generates an empty program with the native backend.
+    ///
+    /// Use `GenAlg::from_generator` to start a run on another backend.
+    fn generate(_rng: &mut dyn RngCore) -> Self {
+        Individual::default()
+    }
+
+    /// Swaps whole sections between the parents if both have loops,
+    /// otherwise joins the first half of this parent with the second half of the other.
+    ///
+    /// Labels are relocated to where their instructions end up in the child,
+    /// branches whose label did not make it into the child are replaced with NOPs.
+    fn crossover(&self, other: &Self, rng: &mut dyn RngCore) -> Self {
+        let child = self
+            .section_crossover(other, rng)
+            .unwrap_or_else(|| self.half_crossover(other));
+
+        debug_assert_eq!(child.validate_labels(), Ok(()));
+        child
+    }
 
-    fn mutate(&mut self) -> () {
+    fn mutate(&mut self, rng: &mut dyn RngCore) {
         let mut new_instr_chance = 0;
         let mut change_operands_chance = 0;
         let mut change_instruction_chance = 0;
@@ -323,7 +570,7 @@ impl Genetic for Individual {
         let mut swap_instr_chance = 50;
         let mut duplicate_instr_chance = 10;
 
-        if self.instructions.len() == 0 {
+        if self.instructions.is_empty() {
             new_instr_chance = 1000;
             change_operands_chance = 0;
             change_instruction_chance = 0;
@@ -341,22 +588,80 @@ impl Genetic for Individual {
             swap_instr_chance,
             duplicate_instr_chance,
         ];
-        let dist = WeightedIndex::new(&weights).unwrap();
-
-        let mut rng = rand::rng();
+        let dist = WeightedIndex::new(weights).unwrap();
 
         let count = rng.random_range(1..=3);
         for _ in 0..count {
-            match choices[dist.sample(&mut rng)] {
-                0 => self.add_rand_instruction(),
-                1 => self.change_operands(),
-                2 => self.change_rand_instruction(),
-                3 => self.remove_rand_instruction(),
-                4 => self.move_rand_instruction(),
-                5 => self.duplicate_rand_instruction(),
+            match choices[dist.sample(rng)] {
+                0 => self.add_rand_instruction(rng),
+                1 => self.change_operands(rng),
+                2 => self.change_rand_instruction(rng),
+                3 => self.remove_rand_instruction(rng),
+                4 => self.move_rand_instruction(rng),
+                5 => self.duplicate_rand_instruction(rng),
                 _ => unreachable!(),
             }
         }
+
+        // Inserting instructions can push a label past the end of the program
+        self.repair_labels();
+    }
+}
+
+impl Distance for Individual {
+    /// Edit distance between the programs without their NOP padding,
+    /// counting inserted, removed and replaced instructions
+    fn distance(&self, other: &Self) -> f32 {
+        let a = &self.instructions[..self.last_nop_index()];
+        let b = &other.instructions[..other.last_nop_index()];
+
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+        let mut current = vec![0; b.len() + 1];
+
+        for (i, instr_a) in a.iter().enumerate() {
+            current[0] = i + 1;
+            for (j, instr_b) in b.iter().enumerate() {
+                let replace = previous[j] + usize::from(instr_a != instr_b);
+                current[j + 1] = replace.min(previous[j + 1] + 1).min(current[j] + 1);
+            }
+            std::mem::swap(&mut previous, &mut current);
+        }
+
+        previous[b.len()] as f32
+    }
+}
+
+impl MultiObjective for Individual {
+    /// Fraction of solved test vectors, followed by the negated mean cycle count,
+    /// program length and number of used registers, so shorter and faster programs win.
+    ///
+    /// A program which can not be evaluated solves nothing and takes the whole cycle budget.
+    fn objectives(&self) -> Vec<f32> {
+        let length = self.last_nop_index();
+        let registers: HashSet<usize> = self.instructions[..length]
+            .iter()
+            .flat_map(|instr| instr.get_registers())
+            .map(|register| register.index())
+            .filter(|&index| index != 0)
+            .collect();
+
+        let (solved, cycles) = match self.evaluate() {
+            Ok(evaluation) if !evaluation.results.is_empty() => {
+                let vectors = evaluation.results.len() as f32;
+                let cycles = evaluation
+                    .results
+                    .iter()
+                    .map(|result| result.cycle_count as f32)
+                    .sum::<f32>();
+                (
+                    1.0 - evaluation.failures.len() as f32 / vectors,
+                    cycles / vectors,
+                )
+            }
+            _ => (0.0, self.task.cycle_budget() as f32),
+        };
+
+        vec![solved, -cycles, -(length as f32), -(registers.len() as f32)]
     }
 }
 
@@ -367,41 +672,57 @@ impl Individual {
     /// ## Arguments
     /// * `input` - A string containing the instructions to parse.
     /// ## Returns
-    /// * An `Individual` containing the parsed instructions.
-    pub fn parse(input: &str) -> Self {
+    /// * An `Individual` containing the parsed instructions,
+    ///   or the position and token of the first invalid instruction or label.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
         let mut instrs: Vec<dlx::Instruction> = vec![];
         let mut labels: Vec<Label> = vec![];
 
         let instrs_parts: Vec<&str> = input.split_terminator("\n").collect();
 
-        println!("{:?}", instrs_parts);
+        for (instr_count, instr) in instrs_parts.into_iter().enumerate() {
+            let line = instr_count + 1;
 
-        let mut instr_count = 0;
-        for instr in instrs_parts {
             // get label, and remove it from string
-            let labeless_instr = if instr.contains(':') {
-                let split_instr = instr.split_once(':').unwrap();
-                labels.push(Label {
-                    name: split_instr.0.trim().to_string(),
-                    location: instr_count,
-                });
-                split_instr.1
-            } else {
-                instr
+            let (labeless_instr, column) = match instr.split_once(':') {
+                Some((label, rest)) => {
+                    let name = label.trim();
+                    let error = |kind| {
+                        let column = label.len() - label.trim_start().len();
+                        ParseError::new(kind, 1, name).offset(line, column)
+                    };
+
+                    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                        return Err(error(ParseErrorKind::InvalidLabel));
+                    }
+                    if labels.iter().any(|l| l.name == name) {
+                        return Err(error(ParseErrorKind::DuplicateLabel));
+                    }
+
+                    labels.push(Label {
+                        name: name.to_string(),
+                        location: instr_count,
+                    });
+                    (rest, label.len() + 1)
+                }
+                None => (instr, 0),
             };
 
-            instrs.push(dlx::Instruction::new(labeless_instr));
-            instr_count += 1;
+            instrs.push(
+                dlx::Instruction::new(labeless_instr).map_err(|err| err.offset(line, column))?,
+            );
         }
 
         while instrs.len() < DLX_INDIV_MAX_SIZE {
             instrs.push(dlx::Instruction::default());
         }
 
-        Individual {
+        Ok(Individual {
             instructions: instrs,
-            labels: labels,
-        }
+            labels,
+            task: Task::soi(),
+            backend: native_backend(),
+        })
     }
 
     /// Creates a new Individual from a string of instructions.
@@ -410,28 +731,39 @@ impl Individual {
     /// ## Arguments
     /// * `input` - A string containing the instructions to parse.
     /// ## Returns
-    /// * An `Individual` containing the parsed instructions.
-    pub fn new(input: &str) -> Self {
+    /// * An `Individual` containing the parsed instructions, or the first parse error.
+    pub fn new(input: &str) -> Result<Self, ParseError> {
         Individual::parse(input)
     }
 }
 
+impl FromStr for Individual {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Individual::parse(s)
+    }
+}
+
+/// Backend of newly parsed individuals
+fn native_backend() -> Arc<dyn EmulatorBackend> {
+    Arc::new(NativeBackend::new())
+}
+
 impl Default for Individual {
     fn default() -> Self {
-        Individual::new("NOP")
+        Individual::parse("NOP").expect("NOP is a valid program")
     }
 }
 
 impl fmt::Display for Individual {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut counter = 0;
-        for instr in &self.instructions {
+        for (counter, instr) in self.instructions.iter().enumerate() {
             // if there is a label where, location == counter, write 'name:'
             if let Some(label) = self.labels.iter().find(|label| label.location == counter) {
                 write!(f, "{}: ", label.name)?;
             }
             writeln!(f, "{}", instr)?;
-            counter += 1;
         }
         Ok(())
     }
@@ -441,6 +773,10 @@ impl fmt::Display for Individual {
 mod test {
 
     use super::*;
+    use crate::individual::dlx::backend::ScriptedBackend;
+    use crate::individual::dlx::emu::{self, EmulatorResult};
+    use crate::individual::dlx::pipeline::{Forwarding, PipelineConfig};
+    use crate::individual::dlx::worker::{WorkerCommand, WorkerPool};
 
     const RAW_INSTRUCTIONS: &str = r#"SUB R4, R4, R4
 SUB R1, R1, R1
@@ -475,7 +811,7 @@ BRLT R8, 0x00000008"#;
 
     #[test]
     fn test_dlx_indiv_parse() {
-        let indiv = Individual::parse(RAW_INSTRUCTIONS);
+        let indiv = Individual::parse(RAW_INSTRUCTIONS).unwrap();
 
         print!("{}", indiv);
 
@@ -489,27 +825,64 @@ BRLT R8, 0x00000008"#;
         );
     }
 
+    #[test]
+    fn test_dlx_indiv_parse_errors() {
+        let err = Individual::parse("ADD R1, R2, R3\nl1: ADDI R1, 0x4, R99").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidRegister);
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 19, "R99"));
+        assert_eq!(err.to_string(), "line 2, column 19: invalid register 'R99'");
+
+        let err = Individual::parse("NOP\nNOP\n  JUMP R1").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnknownOpcode);
+        assert_eq!((err.line, err.column, err.token.as_str()), (3, 3, "JUMP"));
+
+        let err = Individual::parse("l1: NOP\n l1: NOP").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::DuplicateLabel);
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 2, "l1"));
+
+        let err = Individual::parse(": NOP").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidLabel);
+
+        assert!("BRZ R1, l1\nl1: NOP".parse::<Individual>().is_ok());
+    }
+
     #[test]
     fn test_dlx_indiv_generate() {
-        let indiv = Individual::generate();
+        let indiv = Individual::generate(&mut rand::rng());
         assert!(indiv.to_string().contains("NOP"));
         assert!(indiv.instructions.len() == DLX_INDIV_MAX_SIZE);
     }
 
     #[test]
     fn test_dlx_last_index() {
-        let indiv = Individual::parse(RAW_INSTRUCTIONS);
+        let indiv = Individual::parse(RAW_INSTRUCTIONS).unwrap();
         assert_eq!(indiv.last_nop_index(), RAW_INSTRUCTIONS_LEN);
 
-        let indiv2 = Individual::generate();
+        let indiv2 = Individual::generate(&mut rand::rng());
         assert_eq!(indiv2.last_nop_index(), 0);
     }
 
+    #[test]
+    fn test_dlx_last_instruction_mutated() {
+        let indiv = Individual::parse("SUB R4, R4, R4").unwrap();
+        let mut indiv_changed = Individual::parse("SUB R4, R4, R4").unwrap();
+
+        for _ in 0..10 {
+            indiv_changed.change_rand_instruction(&mut rand::rng());
+        }
+
+        assert_ne!(
+            indiv_changed.instructions[0].to_string(),
+            indiv.instructions[0].to_string()
+        );
+        assert_eq!(indiv.last_nop_index(), 1);
+    }
+
     #[test]
     fn test_dlx_rand_instruction() {
-        let mut indiv = Individual::generate();
+        let mut indiv = Individual::generate(&mut rand::rng());
         for _ in 0..10 {
-            indiv.add_rand_instruction();
+            indiv.add_rand_instruction(&mut rand::rng());
         }
 
         print!("{}", indiv);
@@ -520,15 +893,15 @@ BRLT R8, 0x00000008"#;
 
     #[test]
     fn test_dlx_change_operands() {
-        let indiv = Individual::parse(RAW_INSTRUCTIONS);
-        let mut indiv_changed = Individual::parse(RAW_INSTRUCTIONS);
+        let indiv = Individual::parse(RAW_INSTRUCTIONS).unwrap();
+        let mut indiv_changed = Individual::parse(RAW_INSTRUCTIONS).unwrap();
 
         for _ in 0..(DLX_INDIV_MAX_SIZE + 10) {
-            indiv_changed.change_operands();
+            indiv_changed.change_operands(&mut rand::rng());
         }
 
-        print!("{}\n", indiv);
-        print!("{}\n", indiv_changed);
+        println!("{}", indiv);
+        println!("{}", indiv_changed);
 
         assert_eq!(indiv_changed.last_nop_index(), RAW_INSTRUCTIONS_LEN);
         assert_eq!(indiv_changed.instructions.len(), DLX_INDIV_MAX_SIZE);
@@ -540,11 +913,11 @@ BRLT R8, 0x00000008"#;
         let mut indiv_changed = Individual::default();
 
         for _ in 0..(DLX_INDIV_MAX_SIZE + 10) {
-            indiv_changed.change_operands();
+            indiv_changed.change_operands(&mut rand::rng());
         }
 
-        print!("{}\n", indiv);
-        print!("{}\n", indiv_changed);
+        println!("{}", indiv);
+        println!("{}", indiv_changed);
 
         assert_eq!(indiv_changed.last_nop_index(), 0);
         assert_eq!(indiv_changed.instructions.len(), DLX_INDIV_MAX_SIZE);
@@ -552,15 +925,15 @@ BRLT R8, 0x00000008"#;
 
     #[test]
     fn test_dlx_change_instructions() {
-        let indiv = Individual::parse(RAW_INSTRUCTIONS);
-        let mut indiv_changed = Individual::parse(RAW_INSTRUCTIONS);
+        let indiv = Individual::parse(RAW_INSTRUCTIONS).unwrap();
+        let mut indiv_changed = Individual::parse(RAW_INSTRUCTIONS).unwrap();
 
         for _ in 0..(DLX_INDIV_MAX_SIZE + 10) {
-            indiv_changed.change_rand_instruction();
+            indiv_changed.change_rand_instruction(&mut rand::rng());
         }
 
-        print!("{}\n", indiv);
-        print!("{}\n", indiv_changed);
+        println!("{}", indiv);
+        println!("{}", indiv_changed);
 
         assert_eq!(indiv_changed.last_nop_index(), RAW_INSTRUCTIONS_LEN);
         assert_eq!(indiv_changed.instructions.len(), DLX_INDIV_MAX_SIZE);
@@ -572,21 +945,134 @@ BRLT R8, 0x00000008"#;
         let mut indiv_changed = Individual::default();
 
         for _ in 0..(DLX_INDIV_MAX_SIZE + 10) {
-            indiv_changed.change_rand_instruction();
+            indiv_changed.change_rand_instruction(&mut rand::rng());
         }
 
-        print!("{}\n", indiv);
-        print!("{}\n", indiv_changed);
+        println!("{}", indiv);
+        println!("{}", indiv_changed);
 
         assert_eq!(indiv_changed.last_nop_index(), 0);
         assert_eq!(indiv_changed.instructions.len(), DLX_INDIV_MAX_SIZE);
     }
 
+    #[test]
+    fn test_dlx_cache_key() {
+        let soi = Individual::new(SOI_ALG_START).unwrap();
+        let same = Individual::new(SOI_ALG_START)
+            .unwrap()
+            .with_task(soi.task().clone());
+        let mut mutated = soi.clone();
+        mutated.instructions[0] = dlx::Instruction::new("ADDI R0, 0x00000011, R12").unwrap();
+        let equal_task = soi.clone().with_task(Arc::new(Task::clone(soi.task())));
+        let other_task = soi
+            .clone()
+            .with_task(Arc::new(Task::clone(soi.task()).with_cycle_budget(30000)));
+        let other_pipeline = soi
+            .clone()
+            .with_backend(Arc::new(NativeBackend::with_pipeline(PipelineConfig {
+                forwarding: Forwarding::full(),
+                ..PipelineConfig::default()
+            })));
+        let scripted =
+            soi.clone()
+                .with_backend(Arc::new(ScriptedBackend::new(emu::run_native_emulator(
+                    &soi,
+                ))));
+
+        assert_eq!(soi.cache_key(), same.cache_key());
+        assert_eq!(soi.cache_key(), equal_task.cache_key());
+        assert_ne!(soi.cache_key(), mutated.cache_key());
+        assert_ne!(soi.cache_key(), other_task.cache_key());
+        assert_ne!(soi.cache_key(), other_pipeline.cache_key());
+        assert_eq!(scripted.cache_key(), None);
+    }
+
+    #[test]
+    fn test_dlx_edit_distance() {
+        let soi = Individual::new(SOI_ALG_START).unwrap();
+        let mut changed = soi.clone();
+        changed.instructions[3] = dlx::Instruction::new("ADDI R0, 0x00000011, R12").unwrap();
+        changed.instructions.remove(0);
+        changed.instructions.push(dlx::Instruction::default());
+
+        assert_eq!(soi.distance(&soi), 0.0);
+        assert_eq!(soi.distance(&changed), 2.0);
+        assert_eq!(changed.distance(&soi), 2.0);
+        assert_eq!(
+            soi.distance(&Individual::default()),
+            soi.last_nop_index() as f32
+        );
+    }
+
+    #[test]
+    fn test_dlx_objectives() {
+        let task = Arc::new(
+            Task::new("double")
+                .with_memory(0x0, &[21])
+                .with_expected(0x4, &[42])
+                .with_cycle_budget(100),
+        );
+        let correct =
+            Individual::new("LDW R1, 0x00000000(R0)\nADD R1, R1, R2\nSTW R2, 0x00000004(R0)")
+                .unwrap()
+                .with_task(task.clone());
+        let wrong = Individual::new("LDW R1, 0x00000000(R0)\nSTW R1, 0x00000004(R0)")
+            .unwrap()
+            .with_task(task);
+
+        let objectives = correct.objectives();
+        assert_eq!(objectives.len(), 4);
+        assert_eq!(objectives[0], 1.0);
+        assert!(objectives[1] < 0.0);
+        assert_eq!(objectives[2..], [-3.0, -2.0]);
+
+        let objectives = wrong.objectives();
+        assert_eq!(objectives[0], 0.0);
+        assert_eq!(objectives[2..], [-2.0, -1.0]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_dlx_serde_keeps_program() {
+        let task = Arc::new(Task::new("custom"));
+        let indiv = Individual::new(SOI_ALG_START).unwrap().with_task(task);
+
+        let json = serde_json::to_string(&indiv).unwrap();
+        let restored: Individual = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, indiv);
+        assert_eq!(restored.labels, indiv.labels);
+        // the task is not serialized
+        assert!(Arc::ptr_eq(restored.task(), &Task::soi()));
+        assert_eq!(
+            restored.fitness(),
+            Individual::new(SOI_ALG_START).unwrap().fitness()
+        );
+    }
+
+    #[test]
+    fn test_dlx_seeded_operators() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        let soi = Individual::new(SOI_ALG_START).unwrap();
+        let variant = |seed| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let mut child = soi.crossover(&soi, &mut rng);
+            for _ in 0..5 {
+                child.mutate(&mut rng);
+            }
+            child
+        };
+
+        assert_eq!(variant(3).to_string(), variant(3).to_string());
+    }
+
     #[test]
     fn test_dlx_rand_instruction_over_limit() {
-        let mut indiv = Individual::generate();
+        let mut indiv = Individual::generate(&mut rand::rng());
         for _ in 0..(DLX_INDIV_MAX_SIZE + 10) {
-            indiv.add_rand_instruction();
+            indiv.add_rand_instruction(&mut rand::rng());
         }
 
         print!("{}", indiv);
@@ -599,8 +1085,352 @@ BRLT R8, 0x00000008"#;
 
     #[test]
     fn test_dlx_instruction_labels() {
-        let indiv = Individual::new(SOI_ALG_START);
+        let indiv = Individual::new(SOI_ALG_START).unwrap();
+
+        let padding = "NOP\n".repeat(DLX_INDIV_MAX_SIZE - SOI_ALG_START.lines().count());
+
+        assert_eq!(indiv.to_string(), format!("{}\n{}", SOI_ALG_START, padding));
+    }
+
+    #[rustfmt::skip]
+    const EXPECTED_MEMORY: [u32; 32] = [
+        1, 3, 6, 10, 15, 21, 28, 36,
+        45, 55, 66, 78, 91, 105, 120, 136,
+        152, 168, 184, 200, 216, 232, 248, 264,
+        280, 296, 312, 328, 344, 360, 376, 392,
+    ];
+
+    const MEMORY_OUTPUT_ADDR: usize = 192;
+    const MEMORY_OUTPUT_SIZE: usize = 32;
+    const MEMORY_OUTPUT_ADDR_END: usize = MEMORY_OUTPUT_ADDR + MEMORY_OUTPUT_SIZE;
+
+    fn emu_result(success: bool, cycle_count: usize, output: &[u32]) -> EmulatorResult {
+        let mut memory = vec![0; MEMORY_OUTPUT_ADDR_END];
+        memory[MEMORY_OUTPUT_ADDR..MEMORY_OUTPUT_ADDR_END].copy_from_slice(output);
+
+        EmulatorResult {
+            success,
+            cycle_count,
+            unknown: false,
+            memory: Some(memory),
+        }
+    }
+
+    #[test]
+    fn test_dlx_fitness_backend() {
+        let correct = Individual::new("ADD R1, R2, R3").unwrap();
+        let wrong = Individual::new("SUB R1, R2, R3").unwrap();
+        let slow = Individual::new("MUL R1, R2, R3").unwrap();
+
+        let backend = Arc::new(
+            ScriptedBackend::new(emu_result(false, 0, &EXPECTED_MEMORY))
+                .with_result(&correct, emu_result(true, 6000, &EXPECTED_MEMORY))
+                .with_result(&wrong, emu_result(true, 6000, &[0; MEMORY_OUTPUT_SIZE]))
+                .with_result(&slow, emu_result(true, 20001, &EXPECTED_MEMORY)),
+        );
+
+        let fitness = |indiv: Individual| indiv.with_backend(backend.clone()).fitness();
+
+        assert_eq!(fitness(correct), 14000.0);
+        assert_eq!(fitness(wrong), 1.0);
+        assert_eq!(fitness(slow), 0.0);
+        assert_eq!(fitness(Individual::default()), 0.0);
+        assert_eq!(backend.calls(), 4);
+    }
+
+    #[test]
+    fn test_dlx_native_fitness() {
+        let indiv = Individual::new(SOI_ALG_START).unwrap();
+
+        assert!(indiv.fitness() > 1.0);
+    }
+
+    #[test]
+    fn test_dlx_fitness_error() {
+        // The worker process can not be started, so the program is never run
+        let indiv = Individual::new(SOI_ALG_START)
+            .unwrap()
+            .with_task(Arc::new(Task::new("empty").with_expected(0x0, &[1])))
+            .with_backend(Arc::new(WorkerPool::with_size(
+                WorkerCommand::new("/nonexistent/emu_worker"),
+                1,
+            )));
+
+        assert!(matches!(indiv.try_fitness(), Err(Error::Emulator(_))));
+        assert_eq!(indiv.fitness(), 0.0);
+    }
+
+    #[test]
+    fn test_dlx_custom_task() {
+        let task = Arc::new(
+            Task::new("double")
+                .with_memory(0x0, &[21])
+                .with_expected(0x4, &[42])
+                .with_cycle_budget(100),
+        );
+        let correct =
+            Individual::new("LDW R1, 0x00000000(R0)\nADD R1, R1, R2\nSTW R2, 0x00000004(R0)")
+                .unwrap()
+                .with_task(task.clone());
+        let wrong = Individual::new("LDW R1, 0x00000000(R0)\nSTW R1, 0x00000004(R0)")
+            .unwrap()
+            .with_task(task.clone());
+
+        assert!(correct.fitness() > 1.0);
+        assert_eq!(wrong.fitness(), 1.0);
+        assert!(Arc::ptr_eq(
+            correct.crossover(&wrong, &mut rand::rng()).task(),
+            &task
+        ));
+    }
+
+    #[test]
+    fn test_dlx_random_vectors() {
+        let task = Arc::new(Task::soi_random(4, &mut rand::rng()));
+
+        let evaluation = Individual::new(SOI_ALG_START)
+            .unwrap()
+            .with_task(task.clone())
+            .evaluate()
+            .unwrap();
+        assert!(evaluation.passed(), "{}", evaluation);
+        assert_eq!(evaluation.results.len(), 5);
+
+        // Storing the expected output of the reference samples only solves the first vector
+        let hard_coded: String = EXPECTED_MEMORY
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                format!(
+                    "ADDI R0, 0x{:08X}, R1\nSTW R1, 0x{:08X}(R0)\n",
+                    value,
+                    0x300 + 4 * i
+                )
+            })
+            .collect();
+        let hard_coded = Individual::new(&hard_coded).unwrap();
+        assert!(hard_coded.fitness() > 1.0);
+
+        let evaluation = hard_coded.with_task(task).evaluate().unwrap();
+        assert_eq!(evaluation.failures.len(), 4);
+        assert_eq!(evaluation.fitness, 1.0);
+    }
+
+    /// SOI with different label names and extra NOPs in every section
+    fn soi_variant() -> Individual {
+        let code = SOI_ALG_START
+            .replace("l1", "outer")
+            .replace("l2", "inner")
+            .replace("h1", "skip_inner")
+            .replace("h2", "skip_outer")
+            .replace(
+                "ADDI R0, 0x00000010, R12\n",
+                "NOP\nADDI R0, 0x00000010, R12\n",
+            )
+            .replace("\ninner: ", "\nNOP\ninner: ")
+            .replace("ADD R3, R9, R3\n", "ADD R3, R9, R3\nNOP\n")
+            .replace(
+                "STW R3, 0x00000300(R1)\n",
+                "STW R3, 0x00000300(R1)\nNOP\nNOP\n",
+            );
+
+        Individual::new(&code).unwrap()
+    }
+
+    #[test]
+    fn test_dlx_splice_relocates_labels() {
+        let soi = Individual::new(SOI_ALG_START).unwrap();
+        let variant = soi_variant();
+        let sections = Sections::find(&soi).unwrap();
+        let variant_sections = Sections::find(&variant).unwrap();
+
+        // Inner loop of the variant in SOI
+        let parts: Vec<SplicePart> = SectionKind::ALL
+            .iter()
+            .map(|&kind| {
+                let own = (&soi, sections.range(kind));
+                let other = (&variant, variant_sections.range(kind));
+                let (source, counterpart) = if kind == SectionKind::InnerLoop {
+                    (other, own)
+                } else {
+                    (own, other)
+                };
+                SplicePart {
+                    source: source.0,
+                    range: source.1,
+                    counterpart: Some(counterpart),
+                }
+            })
+            .collect();
+        let child = soi.splice(&parts).unwrap();
+
+        assert_eq!(child.get_label_position("l1"), Some(10));
+        assert_eq!(child.get_label_position("inner"), Some(15));
+        assert_eq!(child.get_label_position("skip_inner"), Some(23));
+        assert_eq!(child.get_label_position("h2"), Some(35));
+        assert_eq!(child.get_label_position("l2"), None);
+        assert!(child.instructions()[24].to_string().ends_with(", inner"));
+        assert!(child.fitness() > 1.0);
+    }
+
+    #[test]
+    fn test_dlx_section_crossover() {
+        let soi = Individual::new(SOI_ALG_START).unwrap();
+        let variant = soi_variant();
+
+        for _ in 0..100 {
+            let child = soi.crossover(&variant, &mut rand::rng());
+            let sections = Sections::find(&child).unwrap();
+
+            assert_ne!(child, soi);
+            assert_ne!(child, variant);
+            for (kind, range) in sections.iter() {
+                assert!(
+                    [&soi, &variant].iter().any(|parent| Sections::find(parent)
+                        .unwrap()
+                        .range(kind)
+                        .len()
+                        == range.len()),
+                    "{} of the child comes from neither parent",
+                    kind
+                );
+            }
+            // Sections of both parents compute the same, so every child has to be correct
+            assert!(child.fitness() > 1.0, "{}", child);
+        }
+    }
+
+    #[test]
+    fn test_dlx_half_crossover_labels() {
+        let soi = Individual::new(SOI_ALG_START).unwrap();
+        let variant = soi_variant();
+        let child = soi.half_crossover(&variant);
+
+        assert_eq!(child.validate_labels(), Ok(()));
+        // First 19 instructions of SOI, then the variant from instruction 21
+        assert_eq!(child.instructions()[19], variant.instructions()[21]);
+        assert_eq!(child.get_label_position("skip_inner"), Some(23));
+        assert_eq!(child.get_label_position("skip_outer"), Some(37));
+
+        // Branches to labels in the halves which were left out are removed
+        for index in [18, 24, 38] {
+            assert_eq!(child.instructions()[index], dlx::Instruction::default());
+        }
+        for label in ["l1", "l2", "h1", "outer", "inner"] {
+            assert_eq!(child.get_label_position(label), None);
+        }
+    }
+
+    #[test]
+    fn test_dlx_half_crossover_shared_label_name() {
+        let first = Individual::new(
+            "ADDI R0, 0x00000004, R1\nBRNZ R1, l1\nADD R1, R1, R2\nADD R2, R2, R3\n\
+             l1: SUB R1, R2, R3\nADD R3, R3, R4\nADD R4, R4, R5\nADD R5, R5, R6",
+        )
+        .unwrap();
+        let second = Individual::new(
+            "ADD R1, R1, R2\nADD R2, R2, R3\nADD R3, R3, R4\nADD R4, R4, R5\n\
+             BRNZ R4, l1\nl1: OR R1, R2, R3\nADD R5, R5, R6\nADD R6, R6, R7",
+        )
+        .unwrap();
+        let child = first.half_crossover(&second);
+
+        assert_eq!(child.validate_labels(), Ok(()));
+        assert_eq!(child.get_label_position("l1"), Some(5));
+        // l1 of the first parent was left out, its branch must not jump to l1 of the second
+        assert_eq!(child.instructions()[1], dlx::Instruction::default());
+        assert_eq!(child.instructions()[4], second.instructions()[4]);
+    }
+
+    #[test]
+    fn test_dlx_validate_labels() {
+        assert_eq!(
+            Individual::new(SOI_ALG_START).unwrap().validate_labels(),
+            Ok(())
+        );
+
+        let missing = Individual::new("BRZ R1, h1\nNOP").unwrap();
+        assert!(
+            missing
+                .validate_labels()
+                .unwrap_err()
+                .contains("missing label 'h1'")
+        );
+
+        let mut repaired = missing.clone();
+        repaired.repair_labels();
+        assert_eq!(repaired.validate_labels(), Ok(()));
+        assert_eq!(repaired.instructions()[0], dlx::Instruction::default());
+
+        // Parsing rejects duplicate labels, so the duplicate is added afterwards
+        let mut duplicate = Individual::new("l1: NOP\nBRZ R1, l1").unwrap();
+        duplicate.labels.push(Label {
+            name: "l1".to_string(),
+            location: 1,
+        });
+        assert!(
+            duplicate
+                .validate_labels()
+                .unwrap_err()
+                .contains("more than once")
+        );
+
+        duplicate.labels = vec![Label {
+            name: "l1".to_string(),
+            location: DLX_INDIV_MAX_SIZE,
+        }];
+        assert!(
+            duplicate
+                .validate_labels()
+                .unwrap_err()
+                .contains("past the end")
+        );
+    }
 
-        assert_eq!(indiv.to_string(), format!("{}\nNOP\nNOP\n", SOI_ALG_START));
+    #[test]
+    fn test_dlx_random_crossovers_keep_labels_valid() {
+        let mut population = vec![
+            Individual::new(SOI_ALG_START).unwrap(),
+            soi_variant(),
+            Individual::new(include_str!("../../../examples/long_code.txt")).unwrap(),
+            Individual::new(RAW_INSTRUCTIONS).unwrap(),
+        ];
+        let mut rng = rand::rng();
+
+        for _ in 0..5000 {
+            let a = &population[rng.random_range(0..population.len())];
+            let b = &population[rng.random_range(0..population.len())];
+
+            let mut child = a.crossover(b, &mut rand::rng());
+            assert_eq!(child.validate_labels(), Ok(()), "{}", child);
+
+            child.mutate(&mut rand::rng());
+            assert_eq!(child.validate_labels(), Ok(()), "{}", child);
+
+            // Keep the population small, so children are crossed over again
+            if population.len() < 40 {
+                population.push(child);
+            } else {
+                let index = rng.random_range(0..population.len());
+                population[index] = child;
+            }
+        }
+    }
+
+    #[test]
+    fn test_dlx_crossover_keeps_backend() {
+        let backend: Arc<dyn EmulatorBackend> =
+            Arc::new(ScriptedBackend::new(emu_result(false, 0, &EXPECTED_MEMORY)));
+        let parent = Individual::new(RAW_INSTRUCTIONS)
+            .unwrap()
+            .with_backend(backend.clone());
+
+        let child = parent.crossover(&Individual::new(SOI_ALG_START).unwrap(), &mut rand::rng());
+
+        assert!(Arc::ptr_eq(child.backend(), &backend));
+        assert_eq!(
+            child,
+            child.clone().with_backend(Arc::new(NativeBackend::new()))
+        );
     }
 }
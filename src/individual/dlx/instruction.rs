@@ -1,15 +1,22 @@
 use core::panic;
-use rand::Rng;
+use rand::{Rng, RngCore};
 use regex::Regex;
 use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use super::Opcode;
 use super::Register;
+use super::opcode::OpcodeType;
+use crate::error::{ParseError, ParseErrorKind};
 
 pub const MAX_REGISTER_FOR_RAND: usize = 10;
 pub const MAX_IMMEDIATE_FOR_RAND: i32 = 200;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Instruction {
     opcode: Opcode,
     registers: Vec<Register>,
@@ -30,17 +37,39 @@ impl Instruction {
         &self.label
     }
 
-    pub fn get_rand() -> Self {
-        let r_opcode = Opcode::rand();
+    pub fn get_registers(&self) -> &[Register] {
+        &self.registers
+    }
+
+    /// Returns the registers read by the instruction and the register written by it.
+    ///
+    /// R0 is never reported, since it is hard-wired to zero.
+    pub fn get_register_usage(&self) -> (Vec<usize>, Option<usize>) {
+        let reg = |i: usize| self.registers.get(i).map(|r| r.index()).filter(|&r| r != 0);
+
+        let (sources, destination) = match self.opcode {
+            Opcode::NOP => (vec![], None),
+            Opcode::LDW => (vec![reg(0)], reg(1)),
+            Opcode::STW => (vec![reg(0), reg(1)], None),
+            opcode if opcode.is_branch() => (vec![reg(1)], None),
+            opcode => match opcode.get_type() {
+                OpcodeType::RType => (vec![reg(0), reg(1)], reg(2)),
+                _ => (vec![reg(0)], reg(1)),
+            },
+        };
+
+        (sources.into_iter().flatten().collect(), destination)
+    }
+
+    pub fn get_rand(rng: &mut dyn RngCore) -> Self {
+        let r_opcode = Opcode::rand(rng);
 
         let r_regs = vec![
-            Register::rand_up_to(MAX_REGISTER_FOR_RAND).unwrap(),
-            Register::rand_up_to(MAX_REGISTER_FOR_RAND).unwrap(),
-            Register::rand_up_to(MAX_REGISTER_FOR_RAND).unwrap(),
+            Register::rand_up_to(MAX_REGISTER_FOR_RAND, rng).unwrap(),
+            Register::rand_up_to(MAX_REGISTER_FOR_RAND, rng).unwrap(),
+            Register::rand_up_to(MAX_REGISTER_FOR_RAND, rng).unwrap(),
         ];
 
-        let mut rng = rand::rng();
-
         let r_imm = rng.random_range(-MAX_IMMEDIATE_FOR_RAND..MAX_IMMEDIATE_FOR_RAND) * 4;
 
         Instruction {
@@ -56,15 +85,15 @@ impl Instruction {
     /// ## Arguments
     /// * `pos` - The position of the register to set. If None, a random register will be set.
     /// * `reg` - The register to set.
-    pub fn set_register(&mut self, pos: Option<usize>, reg: Register) {
-        if self.registers.len() == 0 {
+    /// * `rng` - Chooses the random register.
+    pub fn set_register(&mut self, pos: Option<usize>, reg: Register, rng: &mut dyn RngCore) {
+        if self.registers.is_empty() {
             return;
         }
 
         match pos {
             Some(p) => self.registers[p] = reg,
             None => {
-                let mut rng = rand::rng();
                 let reg_index = rng.random_range(0..self.registers.len());
                 self.registers[reg_index] = reg;
             }
@@ -75,6 +104,10 @@ impl Instruction {
         self.immidiate = imm;
     }
 
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
     fn format_instr(&self) -> String {
         // String starting with opcode
         let mut output_str = self.opcode.to_string().clone();
@@ -120,16 +153,23 @@ impl Instruction {
         output_str
     }
 
-    /// Splits the operands of an instruction into a vector of strings.
+    /// Splits the operands of an instruction into a vector of strings, together with their byte offsets.
     ///
     /// Assumes that operands consist of only alphanumeric characters, so any not alphanumeric characters are treated as separators.
-    fn split_operands(input: &str) -> Vec<String> {
+    fn split_operands(input: &str) -> Vec<(usize, &str)> {
         let re = Regex::new(r"0x[0-9a-fA-F]+|R\d+|\d+|[a-zA-Z_][a-zA-Z0-9_]*").unwrap();
         re.find_iter(input)
-            .map(|m| m.as_str().to_string())
+            .map(|m| (m.start(), m.as_str()))
             .collect()
     }
 
+    /// Parses a hex immediate. Immediates are printed as 32-bit two's complement,
+    /// so negative values do not fit in an i32 directly.
+    fn parse_immidiate(operand: &str) -> Option<i32> {
+        let hex = operand.strip_prefix("0x")?;
+        u32::from_str_radix(hex, 16).ok().map(|value| value as i32)
+    }
+
     /// Parses an instruction string into an Instruction struct.
     ///
     /// The instruction string should be in the format "OPCODE FORMAT", where OPCODE is the opcode and FORMAT is the format specified by the opcode.
@@ -137,28 +177,33 @@ impl Instruction {
     /// * `instr` - The instruction string to parse.
     ///
     /// ## Returns
-    /// * `Some(Instruction)` if the instruction string is valid and can be parsed.
-    /// * `None` if the instruction string is invalid or cannot be parsed.
-    fn parse_instr(instr: &str) -> Option<Self> {
+    /// * `Ok(Instruction)` if the instruction string is valid and can be parsed.
+    /// * `Err(ParseError)` with the column and the offending token otherwise. The error is on line 1.
+    fn parse_instr(instr: &str) -> Result<Self, ParseError> {
         // First get the opcode
-        let input = instr.trim();
-        let instr_parts: Vec<&str> = input.split_whitespace().collect();
-        if instr_parts.len() == 0 {
-            return None;
-        }
+        let input = instr.trim_start();
+        let indent = instr.len() - input.len();
+        let input = input.trim_end();
 
-        let opcode = Opcode::parse_instr(instr)?;
+        let opcode_str = input.split_whitespace().next().unwrap_or_default();
+        let opcode = Opcode::parse_instr(input).ok_or_else(|| {
+            ParseError::new(ParseErrorKind::UnknownOpcode, indent + 1, opcode_str)
+        })?;
 
         // Get format from opcode
         let mut format_chars = opcode.get_format().chars().peekable();
 
-        // Get operands vector from instruction
-        let operands_str = if let Some((_, rest)) = input.split_once(' ') {
-            rest
-        } else {
-            ""
+        // Get operands vector from instruction, with offsets in the instruction string
+        let operands_offset = indent + opcode_str.len();
+        let operands_parts: Vec<(usize, &str)> =
+            Instruction::split_operands(&input[opcode_str.len()..])
+                .into_iter()
+                .map(|(offset, operand)| (operands_offset + offset, operand))
+                .collect();
+
+        let error = |kind: ParseErrorKind, (offset, token): (usize, &str)| {
+            ParseError::new(kind, offset + 1, token)
         };
-        let operands_parts: Vec<String> = Instruction::split_operands(operands_str);
 
         // Temp output vars
         let mut regs: Vec<(usize, Register)> = vec![];
@@ -170,33 +215,29 @@ impl Instruction {
         // Format parser
         while let Some(c) = format_chars.next() {
             if c == 'r' {
-                if let Some(digit_char) = format_chars.next() {
-                    if let Some(idx) = digit_char.to_digit(10) {
-                        if current_operand_index < operands_parts.len() {
-                            regs.push((
-                                (idx - 1) as usize,
-                                Register::parse_reg(&operands_parts[current_operand_index])
-                                    .unwrap(),
-                            ));
-                        }
-                        current_operand_index += 1;
+                if let Some(digit_char) = format_chars.next()
+                    && let Some(idx) = digit_char.to_digit(10)
+                {
+                    if let Some(&part) = operands_parts.get(current_operand_index) {
+                        let reg = Register::parse_reg(part.1)
+                            .ok_or_else(|| error(ParseErrorKind::InvalidRegister, part))?;
+                        regs.push(((idx - 1) as usize, reg));
                     }
+                    current_operand_index += 1;
                 }
             } else if c == 'i' {
-                if current_operand_index < operands_parts.len() {
-                    let cleaned_immidiate =
-                        operands_parts[current_operand_index].trim_start_matches("0x");
-                    immidiate = i32::from_str_radix(cleaned_immidiate, 16).unwrap();
+                if let Some(&part) = operands_parts.get(current_operand_index) {
+                    immidiate = Instruction::parse_immidiate(part.1)
+                        .ok_or_else(|| error(ParseErrorKind::InvalidImmediate, part))?;
                 }
                 current_operand_index += 1;
             } else if c == 'j' {
-                if current_operand_index < operands_parts.len() {
-                    let operand = &operands_parts[current_operand_index];
-                    if operand.starts_with("0x") {
-                        immidiate =
-                            i32::from_str_radix(operand.trim_start_matches("0x"), 16).unwrap();
+                if let Some(&part) = operands_parts.get(current_operand_index) {
+                    if part.1.starts_with(|c: char| c.is_ascii_digit()) {
+                        immidiate = Instruction::parse_immidiate(part.1)
+                            .ok_or_else(|| error(ParseErrorKind::InvalidImmediate, part))?;
                     } else {
-                        label = Some(operand.clone());
+                        label = Some(part.1.to_string());
                     }
                 }
                 current_operand_index += 1;
@@ -205,11 +246,16 @@ impl Instruction {
 
         // Check if correct number of operands was used
         if current_operand_index != operands_parts.len() {
-            panic!(
-                "Invalid number of operands expected {} operands, got {}",
-                current_operand_index,
-                operands_parts.len()
-            );
+            let kind = ParseErrorKind::OperandCount {
+                expected: current_operand_index,
+                found: operands_parts.len(),
+            };
+
+            // Point at the first extra operand, or at the whole instruction if operands are missing
+            return Err(match operands_parts.get(current_operand_index) {
+                Some(&part) => error(kind, part),
+                None => ParseError::new(kind, indent + 1, input),
+            });
         }
 
         // Convert from (index, Register) to Register vector
@@ -219,16 +265,17 @@ impl Instruction {
             registers[index] = reg;
         }
 
-        Some(Instruction {
-            opcode: opcode,
-            registers: registers,
-            immidiate: immidiate,
-            label: label,
+        Ok(Instruction {
+            opcode,
+            registers,
+            immidiate,
+            label,
         })
     }
 
-    pub fn new(instr: &str) -> Self {
-        Instruction::parse_instr(instr).unwrap()
+    /// Parses a single instruction, see `parse_instr`
+    pub fn new(instr: &str) -> Result<Self, ParseError> {
+        Instruction::parse_instr(instr)
     }
 
     pub fn get_bytes(&self) -> u32 {
@@ -248,6 +295,14 @@ impl Default for Instruction {
     }
 }
 
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Instruction::parse_instr(s)
+    }
+}
+
 impl fmt::Display for Instruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.format_instr())
@@ -267,31 +322,85 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Invalid number of operands expected 3 operands, got 4")]
     fn test_parse_too_many_operands() {
-        let inst = Instruction::parse_instr("ADD R1, R2, R3, R4");
-        assert!(inst.is_none());
+        let err = Instruction::parse_instr("ADD R1, R2, R3, R4").unwrap_err();
+
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::OperandCount {
+                expected: 3,
+                found: 4
+            }
+        );
+        assert_eq!((err.column, err.token.as_str()), (17, "R4"));
     }
 
     #[test]
-    #[should_panic(expected = "Invalid number of operands expected 0 operands, got 4")]
     fn test_parse_too_many_operands2() {
-        let inst = Instruction::parse_instr("NOP R1, R2, R3, R4");
-        assert!(inst.is_none());
+        let err = Instruction::parse_instr("NOP R1, R2, R3, R4").unwrap_err();
+
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::OperandCount {
+                expected: 0,
+                found: 4
+            }
+        );
+        assert_eq!((err.column, err.token.as_str()), (5, "R1"));
     }
 
     #[test]
-    #[should_panic(expected = "Invalid number of operands expected 3 operands, got 2")]
     fn test_parse_too_less_operands() {
-        let inst = Instruction::parse_instr("ADD R1, R2");
-        assert!(inst.is_none());
+        let err = Instruction::parse_instr("  ADD R1, R2").unwrap_err();
+
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::OperandCount {
+                expected: 3,
+                found: 2
+            }
+        );
+        assert_eq!((err.column, err.token.as_str()), (3, "ADD R1, R2"));
     }
 
     #[test]
-    #[should_panic(expected = "Invalid number of operands expected 3 operands, got 0")]
     fn test_parse_too_less_operands2() {
-        let inst = Instruction::parse_instr("ADD");
-        assert!(inst.is_none());
+        let err = Instruction::parse_instr("ADD").unwrap_err();
+
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::OperandCount {
+                expected: 3,
+                found: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_tokens() {
+        let err = Instruction::parse_instr("FOO R1, R2, R3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnknownOpcode);
+        assert_eq!((err.column, err.token.as_str()), (1, "FOO"));
+
+        let err = Instruction::parse_instr("ADD R1, R32, R3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidRegister);
+        assert_eq!((err.column, err.token.as_str()), (9, "R32"));
+
+        let err = Instruction::parse_instr("ADDI R1, 12, R3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidImmediate);
+        assert_eq!((err.column, err.token.as_str()), (10, "12"));
+
+        let err = Instruction::parse_instr("BRZ R1, 0x1FFFFFFFF").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidImmediate);
+
+        assert!(Instruction::parse_instr("").is_err());
+    }
+
+    #[test]
+    fn test_instruction_from_str() {
+        let inst: Instruction = "ADD R4, R3, R2".parse().unwrap();
+
+        assert_eq!(inst, Instruction::new("ADD R4, R3, R2").unwrap());
     }
 
     #[test]
@@ -332,10 +441,10 @@ mod test {
 
     #[test]
     fn test_instruction_parse() {
-        let add_inst = Instruction::new("ADD R4, R3, R2");
-        let ldw_inst = Instruction::new("LDW R8, 0x0020(R6)");
-        let nop_inst = Instruction::new("NOP");
-        let brz_inst = Instruction::new("BRZ R3, 0x0020");
+        let add_inst = Instruction::new("ADD R4, R3, R2").unwrap();
+        let ldw_inst = Instruction::new("LDW R8, 0x0020(R6)").unwrap();
+        let nop_inst = Instruction::new("NOP").unwrap();
+        let brz_inst = Instruction::new("BRZ R3, 0x0020").unwrap();
 
         assert_eq!(ldw_inst.to_string(), "LDW R8, 0x00000020(R6)");
         assert_eq!(add_inst.to_string(), "ADD R4, R3, R2");
@@ -343,9 +452,17 @@ mod test {
         assert_eq!(brz_inst.to_string(), "BRZ R3, 0x00000020");
     }
 
+    #[test]
+    fn test_instruction_parse_negative_immediate() {
+        let subi_inst = Instruction::new("SUBI R1, 0xFFFFFFFC, R2").unwrap();
+
+        assert_eq!(subi_inst.immidiate, -4);
+        assert_eq!(subi_inst.to_string(), "SUBI R1, 0xFFFFFFFC, R2");
+    }
+
     #[test]
     fn test_instruction_label() {
-        let brz_inst = Instruction::new("BRZ R3, h1");
+        let brz_inst = Instruction::new("BRZ R3, h1").unwrap();
 
         assert_eq!(brz_inst.to_string(), "BRZ R3, h1");
     }
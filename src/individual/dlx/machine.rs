@@ -0,0 +1,258 @@
+use std::fmt;
+
+use super::pipeline::{Pipeline, PipelineConfig, PipelineReport};
+use super::{Individual, Instruction};
+
+/// Number of 32-bit words in the machine memory
+pub const MEMORY_SIZE: usize = 256;
+
+/// Number of general purpose registers
+pub const REGISTER_COUNT: usize = 32;
+
+/// Reasons for which the machine stops executing a program before reaching its end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    DivisionByZero,
+    InvalidMemoryAccess(i32),
+    InvalidBranchTarget(i32),
+    UnknownLabel(String),
+    /// The program ran longer than the cycle budget of its task
+    CycleLimitExceeded(usize),
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fault::DivisionByZero => write!(f, "division by zero"),
+            Fault::InvalidMemoryAccess(addr) => {
+                write!(f, "invalid memory access at 0x{:08X}", addr)
+            }
+            Fault::InvalidBranchTarget(addr) => write!(f, "invalid branch target 0x{:08X}", addr),
+            Fault::UnknownLabel(label) => write!(f, "unknown label '{}'", label),
+            Fault::CycleLimitExceeded(limit) => write!(f, "cycle limit of {} exceeded", limit),
+        }
+    }
+}
+
+/// Branch waiting for its delay slots to be executed
+#[derive(Debug, Clone, Copy)]
+struct PendingBranch {
+    pc: usize,
+    /// Target of the branch, if it is taken
+    target: Option<usize>,
+    /// Delay slots left to execute
+    remaining: usize,
+}
+
+/// State of the DLX machine: registers, memory and program counter.
+///
+/// Besides the architectural state, the machine feeds executed instructions
+/// to the pipeline model and keeps track of whether any uninitialized register was read.
+#[derive(Debug, Clone)]
+pub struct Machine {
+    registers: [i32; REGISTER_COUNT],
+    written: [bool; REGISTER_COUNT],
+    memory: Vec<u32>,
+    pc: usize,
+    pending_branch: Option<PendingBranch>,
+    pipeline: Pipeline,
+    unknown: bool,
+}
+
+impl Machine {
+    /// Creates a new machine with the given memory image and the reference pipeline configuration.
+    ///
+    /// The memory is resized to `MEMORY_SIZE` words.
+    pub fn new(memory: &[u32]) -> Self {
+        Machine::with_pipeline(memory, PipelineConfig::default())
+    }
+
+    /// Creates a new machine with the given memory image and pipeline configuration.
+    pub fn with_pipeline(memory: &[u32], config: PipelineConfig) -> Self {
+        let mut memory = memory.to_vec();
+        memory.resize(MEMORY_SIZE, 0);
+
+        Machine {
+            registers: [0; REGISTER_COUNT],
+            written: [false; REGISTER_COUNT],
+            memory,
+            pc: 0,
+            pending_branch: None,
+            pipeline: Pipeline::new(config),
+            unknown: false,
+        }
+    }
+
+    /// Getter for the value of a register
+    pub fn register(&self, index: usize) -> i32 {
+        self.registers[index]
+    }
+
+    /// Getter for memory
+    pub fn memory(&self) -> &[u32] {
+        &self.memory
+    }
+
+    /// Getter for program counter (index of the next instruction)
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Returns true if an uninitialized register was read
+    pub fn unknown(&self) -> bool {
+        self.unknown
+    }
+
+    /// Returns the number of cycles taken so far.
+    ///
+    /// Includes filling the pipeline and the halt instruction fetched past the end of the program.
+    pub fn cycle_count(&self) -> usize {
+        self.pipeline.cycle_count()
+    }
+
+    /// Returns the pipeline timing report of the instructions executed so far
+    pub fn pipeline_report(&self) -> PipelineReport {
+        self.pipeline.report()
+    }
+
+    /// Runs the program until its end, or until it goes over the cycle budget of its task.
+    ///
+    /// Trailing NOPs are not part of the program, unless they are in the delay slots of a branch.
+    pub fn run(&mut self, indiv: &Individual) -> Result<(), Fault> {
+        let cycle_limit = indiv.task().cycle_budget();
+        let instructions = indiv.instructions();
+        let targets = Machine::resolve_targets(indiv)?;
+        let program_len = indiv.last_nop_index();
+
+        while self.pc < program_len
+            || (self.pending_branch.is_some() && self.pc < instructions.len())
+        {
+            let pc = self.pc;
+            self.step(&instructions[pc], targets[pc])?;
+
+            if self.cycle_count() > cycle_limit {
+                return Err(Fault::CycleLimitExceeded(cycle_limit));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves branch targets of every instruction to instruction indices.
+    ///
+    /// Immediate branch targets are byte addresses of the target instruction.
+    fn resolve_targets(indiv: &Individual) -> Result<Vec<Option<Result<usize, i32>>>, Fault> {
+        indiv
+            .instructions()
+            .iter()
+            .map(|instr| {
+                if !instr.get_opcode().is_branch() {
+                    return Ok(None);
+                }
+
+                match instr.get_label() {
+                    Some(label) => indiv
+                        .get_label_position(label)
+                        .map(|pos| Some(Ok(pos)))
+                        .ok_or_else(|| Fault::UnknownLabel(label.clone())),
+                    None => {
+                        let addr = instr.get_immidiate();
+                        if addr < 0 || addr % 4 != 0 {
+                            Ok(Some(Err(addr)))
+                        } else {
+                            Ok(Some(Ok(addr as usize / 4)))
+                        }
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Executes a single instruction and advances the program counter.
+    fn step(
+        &mut self,
+        instr: &Instruction,
+        target: Option<Result<usize, i32>>,
+    ) -> Result<(), Fault> {
+        self.pipeline.issue(self.pc, instr);
+
+        let taken = instr.get_opcode().execute(self, instr)?;
+
+        let mut next_pc = self.pc + 1;
+
+        if let Some(mut branch) = self.pending_branch {
+            branch.remaining -= 1;
+            if branch.remaining == 0 {
+                next_pc = self.resolve_branch(branch, next_pc);
+            } else {
+                self.pending_branch = Some(branch);
+            }
+        }
+
+        if instr.get_opcode().is_branch() {
+            let target = match target {
+                Some(Ok(branch_target)) => taken.then_some(branch_target),
+                Some(Err(addr)) if taken => return Err(Fault::InvalidBranchTarget(addr)),
+                _ => None,
+            };
+
+            let branch = PendingBranch {
+                pc: self.pc,
+                target,
+                remaining: self.pipeline.config().branch.delay_slots,
+            };
+
+            if branch.remaining == 0 {
+                next_pc = self.resolve_branch(branch, next_pc);
+            } else {
+                self.pending_branch = Some(branch);
+            }
+        }
+
+        self.pc = next_pc;
+        Ok(())
+    }
+
+    /// Finishes the branch after its delay slots. Returns the next program counter.
+    fn resolve_branch(&mut self, branch: PendingBranch, next_pc: usize) -> usize {
+        self.pending_branch = None;
+        self.pipeline
+            .resolve_branch(branch.pc, branch.target.is_some());
+        branch.target.unwrap_or(next_pc)
+    }
+
+    /// Reads a register, marking the result as unknown if it was never written
+    pub(crate) fn read_reg(&mut self, index: usize) -> i32 {
+        if index != 0 && !self.written[index] {
+            self.unknown = true;
+        }
+        self.registers[index]
+    }
+
+    /// Writes a register. Writes to R0 are ignored, since it is hard-wired to zero.
+    pub(crate) fn write_reg(&mut self, index: usize, value: i32) {
+        if index != 0 {
+            self.registers[index] = value;
+            self.written[index] = true;
+        }
+    }
+
+    fn memory_index(&self, addr: i32) -> Result<usize, Fault> {
+        if addr < 0 || addr % 4 != 0 || addr as usize / 4 >= self.memory.len() {
+            return Err(Fault::InvalidMemoryAccess(addr));
+        }
+        Ok(addr as usize / 4)
+    }
+
+    /// Reads a word from memory. The address is in bytes and has to be word aligned.
+    pub(crate) fn load(&self, addr: i32) -> Result<u32, Fault> {
+        Ok(self.memory[self.memory_index(addr)?])
+    }
+
+    /// Writes a word to memory. The address is in bytes and has to be word aligned.
+    pub(crate) fn store(&mut self, addr: i32, value: u32) -> Result<(), Fault> {
+        let index = self.memory_index(addr)?;
+        self.memory[index] = value;
+        Ok(())
+    }
+}
@@ -1,11 +1,20 @@
+pub mod backend;
 pub mod emu;
 pub mod individual;
 pub mod instruction;
+pub mod machine;
 pub mod opcode;
+pub mod pipeline;
 pub mod register;
+pub mod section;
+pub mod task;
+pub mod worker;
 
+pub use backend::EmulatorBackend;
 pub use individual::Individual;
 pub use instruction::Instruction;
+pub use machine::Machine;
 pub use opcode::Opcode;
 pub use register::Register;
-
+pub use task::{Task, TestVector};
+pub use worker::WorkerPool;
@@ -1,8 +1,12 @@
 use std::fmt;
 
-use rand::seq::IndexedRandom;
+use rand::{RngCore, seq::IndexedRandom};
 
-use super::Register;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Instruction;
+use super::machine::{Fault, Machine};
 
 /// Represents the type of opcode in the instruction set architecture.
 /// Currently unused
@@ -21,6 +25,7 @@ pub enum OpcodeType {
 /// - `$value`: The numeric value associated with the opcode (used in machine code).
 /// - `$type`: The `OpcodeType` (RType, IType, JType) for the opcode.
 /// - `$format`: A format string for how to represent the operands of the opcode.
+/// - `$func`: A handler executing the opcode on a `Machine`.
 ///
 macro_rules! define_opcodes {
     ($( $name:ident = $value:expr, $type:expr, $format:expr, $func:expr ),*) => {
@@ -28,7 +33,8 @@ macro_rules! define_opcodes {
         /// Enum representing the opcodes in the instruction set architecture.
         ///
         /// The byte value, type, and format string are associated with each variant.
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u8)]
         pub enum Opcode {
             $(
@@ -56,10 +62,12 @@ macro_rules! define_opcodes {
             }
 
             /// Execute an instruction based on the opcode
-            pub fn execute(&self, regs: &mut Vec<&mut Register>, imm: &mut i32) -> () {
+            ///
+            /// Returns true if the instruction is a branch and the branch is taken.
+            pub fn execute(&self, machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
                 match self {
                     $(
-                        Opcode::$name => $func(regs, imm),
+                        Opcode::$name => $func(machine, instr),
                     )*
                 }
             }
@@ -144,40 +152,210 @@ pub const BRANCH_OPCODES: &[Opcode] = &[
 ];
 
 impl Opcode {
-    pub fn rand() -> Self {
-        let mut rng = rand::rng();
-        *ALL_RAND_OPCODES.choose(&mut rng).unwrap()
+    pub fn rand(rng: &mut dyn RngCore) -> Self {
+        *ALL_RAND_OPCODES.choose(rng).unwrap()
+    }
+
+    /// Returns true if the opcode is a conditional branch
+    pub fn is_branch(&self) -> bool {
+        BRANCH_OPCODES.contains(self)
+    }
+}
+
+/// Returns the number of register at position `pos` of the instruction
+fn reg(instr: &Instruction, pos: usize) -> usize {
+    instr.get_registers()[pos].index()
+}
+
+/// Executes an R-type ALU instruction: `r3 = op(r1, r2)`
+fn r_type(
+    machine: &mut Machine,
+    instr: &Instruction,
+    op: fn(i32, i32) -> Result<i32, Fault>,
+) -> Result<bool, Fault> {
+    let a = machine.read_reg(reg(instr, 0));
+    let b = machine.read_reg(reg(instr, 1));
+    machine.write_reg(reg(instr, 2), op(a, b)?);
+    Ok(false)
+}
+
+/// Executes an I-type ALU instruction: `r2 = op(r1, i)`
+fn i_type(
+    machine: &mut Machine,
+    instr: &Instruction,
+    op: fn(i32, i32) -> Result<i32, Fault>,
+) -> Result<bool, Fault> {
+    let a = machine.read_reg(reg(instr, 0));
+    machine.write_reg(reg(instr, 1), op(a, instr.get_immidiate())?);
+    Ok(false)
+}
+
+/// Executes a branch instruction: branch is taken if `cond(r2)`
+fn branch(
+    machine: &mut Machine,
+    instr: &Instruction,
+    cond: fn(i32) -> bool,
+) -> Result<bool, Fault> {
+    Ok(cond(machine.read_reg(reg(instr, 1))))
+}
+
+fn add(a: i32, b: i32) -> Result<i32, Fault> {
+    Ok(a.wrapping_add(b))
+}
+
+fn sub(a: i32, b: i32) -> Result<i32, Fault> {
+    Ok(a.wrapping_sub(b))
+}
+
+fn mul(a: i32, b: i32) -> Result<i32, Fault> {
+    Ok(a.wrapping_mul(b))
+}
+
+fn div(a: i32, b: i32) -> Result<i32, Fault> {
+    if b == 0 {
+        return Err(Fault::DivisionByZero);
     }
+    Ok(a.wrapping_div(b))
+}
+
+fn and(a: i32, b: i32) -> Result<i32, Fault> {
+    Ok(a & b)
+}
+
+fn or(a: i32, b: i32) -> Result<i32, Fault> {
+    Ok(a | b)
+}
+
+fn xor(a: i32, b: i32) -> Result<i32, Fault> {
+    Ok(a ^ b)
+}
+
+fn nop_handler(_machine: &mut Machine, _instr: &Instruction) -> Result<bool, Fault> {
+    Ok(false)
+}
+
+fn add_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    r_type(machine, instr, add)
+}
+
+fn sub_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    r_type(machine, instr, sub)
+}
+
+fn mul_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    r_type(machine, instr, mul)
+}
+
+fn div_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    r_type(machine, instr, div)
+}
+
+fn and_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    r_type(machine, instr, and)
+}
+
+fn or_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    r_type(machine, instr, or)
+}
+
+fn xor_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    r_type(machine, instr, xor)
+}
+
+fn addi_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    i_type(machine, instr, add)
+}
+
+fn subi_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    i_type(machine, instr, sub)
+}
+
+fn muli_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    i_type(machine, instr, mul)
+}
+
+fn divi_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    i_type(machine, instr, div)
+}
+
+fn andi_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    i_type(machine, instr, and)
+}
+
+fn ori_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    i_type(machine, instr, or)
+}
+
+fn xori_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    i_type(machine, instr, xor)
+}
+
+/// `r2 = M[r1 + i]`
+fn ldw_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    let base = machine.read_reg(reg(instr, 0));
+    let value = machine.load(base.wrapping_add(instr.get_immidiate()))?;
+    machine.write_reg(reg(instr, 1), value as i32);
+    Ok(false)
+}
+
+/// `M[r1 + i] = r2`
+fn stw_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    let base = machine.read_reg(reg(instr, 0));
+    let value = machine.read_reg(reg(instr, 1));
+    machine.store(base.wrapping_add(instr.get_immidiate()), value as u32)?;
+    Ok(false)
+}
+
+fn brz_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    branch(machine, instr, |v| v == 0)
+}
+
+fn brnz_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    branch(machine, instr, |v| v != 0)
+}
+
+fn brgt_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    branch(machine, instr, |v| v > 0)
+}
+
+fn brge_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    branch(machine, instr, |v| v >= 0)
+}
+
+fn brlt_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    branch(machine, instr, |v| v < 0)
 }
 
-fn nop_handler(_regs: &mut Vec<&mut Register>, _imm: &mut i32) {}
+fn brle_handler(machine: &mut Machine, instr: &Instruction) -> Result<bool, Fault> {
+    branch(machine, instr, |v| v <= 0)
+}
 
 // Define opcodes here:
-//  Opcode | Byte representation | Type | Format of instruction
+//  Opcode | Byte representation | Type | Format of instruction | Handler
 define_opcodes! {
     NOP   = 0x00, OpcodeType::RType, "", nop_handler,
-    ADD   = 0x01, OpcodeType::RType, "r1, r2, r3", nop_handler,
-    LDW   = 0x02, OpcodeType::IType, "r2, i(r1)", nop_handler,
-    STW   = 0x03, OpcodeType::IType, "r2, i(r1)", nop_handler,
-    SUB   = 0x04, OpcodeType::RType, "r1, r2, r3", nop_handler,
-    MUL   = 0x05, OpcodeType::RType, "r1, r2, r3", nop_handler,
-    DIV   = 0x06, OpcodeType::RType, "r1, r2, r3", nop_handler,
-    AND   = 0x07, OpcodeType::RType, "r1, r2, r3", nop_handler,
-    OR    = 0x08, OpcodeType::RType, "r1, r2, r3", nop_handler,
-    XOR   = 0x09, OpcodeType::RType, "r1, r2, r3", nop_handler,
-    SUBI  = 0x0B, OpcodeType::IType, "r1, i, r2", nop_handler,
-    MULI  = 0x0C, OpcodeType::IType, "r1, i, r2", nop_handler,
-    ADDI  = 0x0A, OpcodeType::IType, "r1, i, r2", nop_handler,
-    DIVI  = 0x0D, OpcodeType::IType, "r1, i, r2", nop_handler,
-    ANDI  = 0x0E, OpcodeType::IType, "r1, i, r2", nop_handler,
-    ORI   = 0x0F, OpcodeType::IType, "r1, i, r2", nop_handler,
-    XORI  = 0x10, OpcodeType::IType, "r1, i, r2", nop_handler,
-    BRZ   = 0x11, OpcodeType::IType, "r2, j", nop_handler,
-    BRNZ  = 0x12, OpcodeType::IType, "r2, j", nop_handler,
-    BRGT  = 0x13, OpcodeType::IType, "r2, j", nop_handler,
-    BRGE  = 0x14, OpcodeType::IType, "r2, j", nop_handler,
-    BRLT  = 0x15, OpcodeType::IType, "r2, j", nop_handler,
-    BRLE  = 0x16, OpcodeType::IType, "r2, j", nop_handler
+    ADD   = 0x01, OpcodeType::RType, "r1, r2, r3", add_handler,
+    LDW   = 0x02, OpcodeType::IType, "r2, i(r1)", ldw_handler,
+    STW   = 0x03, OpcodeType::IType, "r2, i(r1)", stw_handler,
+    SUB   = 0x04, OpcodeType::RType, "r1, r2, r3", sub_handler,
+    MUL   = 0x05, OpcodeType::RType, "r1, r2, r3", mul_handler,
+    DIV   = 0x06, OpcodeType::RType, "r1, r2, r3", div_handler,
+    AND   = 0x07, OpcodeType::RType, "r1, r2, r3", and_handler,
+    OR    = 0x08, OpcodeType::RType, "r1, r2, r3", or_handler,
+    XOR   = 0x09, OpcodeType::RType, "r1, r2, r3", xor_handler,
+    SUBI  = 0x0B, OpcodeType::IType, "r1, i, r2", subi_handler,
+    MULI  = 0x0C, OpcodeType::IType, "r1, i, r2", muli_handler,
+    ADDI  = 0x0A, OpcodeType::IType, "r1, i, r2", addi_handler,
+    DIVI  = 0x0D, OpcodeType::IType, "r1, i, r2", divi_handler,
+    ANDI  = 0x0E, OpcodeType::IType, "r1, i, r2", andi_handler,
+    ORI   = 0x0F, OpcodeType::IType, "r1, i, r2", ori_handler,
+    XORI  = 0x10, OpcodeType::IType, "r1, i, r2", xori_handler,
+    BRZ   = 0x11, OpcodeType::IType, "r2, j", brz_handler,
+    BRNZ  = 0x12, OpcodeType::IType, "r2, j", brnz_handler,
+    BRGT  = 0x13, OpcodeType::IType, "r2, j", brgt_handler,
+    BRGE  = 0x14, OpcodeType::IType, "r2, j", brge_handler,
+    BRLT  = 0x15, OpcodeType::IType, "r2, j", brlt_handler,
+    BRLE  = 0x16, OpcodeType::IType, "r2, j", brle_handler
 }
 
 #[cfg(test)]
@@ -213,6 +391,135 @@ mod tests {
         assert_eq!(Opcode::BRZ.get_type(), OpcodeType::IType);
     }
 
+    /// Executes a single instruction on a machine with R1 = 12 and R2 = -5
+    fn exec(instr: &str) -> (Machine, Result<bool, Fault>) {
+        let mut machine = Machine::new(&[7, 8, 9]);
+        machine.write_reg(1, 12);
+        machine.write_reg(2, -5);
+
+        let instr = Instruction::new(instr).unwrap();
+        let result = instr.get_opcode().execute(&mut machine, &instr);
+        (machine, result)
+    }
+
+    fn exec_reg(instr: &str, reg: usize) -> i32 {
+        let (machine, result) = exec(instr);
+        assert_eq!(result, Ok(false));
+        machine.register(reg)
+    }
+
+    #[test]
+    fn test_execute_nop() {
+        let (machine, result) = exec("NOP");
+        assert_eq!(result, Ok(false));
+        assert_eq!(machine.register(1), 12);
+        assert_eq!(machine.memory()[..3], [7, 8, 9]);
+    }
+
+    #[test]
+    fn test_execute_r_type() {
+        assert_eq!(exec_reg("ADD R1, R2, R3", 3), 7);
+        assert_eq!(exec_reg("SUB R1, R2, R3", 3), 17);
+        assert_eq!(exec_reg("MUL R1, R2, R3", 3), -60);
+        assert_eq!(exec_reg("DIV R1, R2, R3", 3), -2);
+        assert_eq!(exec_reg("AND R1, R2, R3", 3), 12 & -5);
+        assert_eq!(exec_reg("OR R1, R2, R3", 3), 12 | -5);
+        assert_eq!(exec_reg("XOR R1, R2, R3", 3), 12 ^ -5);
+    }
+
+    #[test]
+    fn test_execute_i_type() {
+        assert_eq!(exec_reg("ADDI R1, 0x00000003, R3", 3), 15);
+        assert_eq!(exec_reg("SUBI R1, 0x00000003, R3", 3), 9);
+        assert_eq!(exec_reg("MULI R1, 0x00000003, R3", 3), 36);
+        assert_eq!(exec_reg("DIVI R1, 0x00000005, R3", 3), 2);
+        assert_eq!(exec_reg("ANDI R1, 0x00000006, R3", 3), 4);
+        assert_eq!(exec_reg("ORI R1, 0x00000003, R3", 3), 15);
+        assert_eq!(exec_reg("XORI R1, 0x00000006, R3", 3), 10);
+    }
+
+    #[test]
+    fn test_execute_wrapping() {
+        let mut machine = Machine::new(&[]);
+        machine.write_reg(1, i32::MAX);
+        machine.write_reg(2, -1);
+
+        let instr = Instruction::new("ADDI R1, 0x00000001, R3").unwrap();
+        instr.get_opcode().execute(&mut machine, &instr).unwrap();
+        assert_eq!(machine.register(3), i32::MIN);
+
+        let instr = Instruction::new("DIV R3, R2, R4").unwrap();
+        instr.get_opcode().execute(&mut machine, &instr).unwrap();
+        assert_eq!(machine.register(4), i32::MIN);
+    }
+
+    #[test]
+    fn test_execute_division_by_zero() {
+        assert_eq!(exec("DIV R1, R0, R3").1, Err(Fault::DivisionByZero));
+        assert_eq!(
+            exec("DIVI R1, 0x00000000, R3").1,
+            Err(Fault::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn test_execute_r0_hard_wired() {
+        assert_eq!(exec_reg("ADDI R1, 0x00000003, R0", 0), 0);
+        assert_eq!(exec_reg("ADD R1, R1, R0", 0), 0);
+        assert_eq!(exec_reg("LDW R0, 0x00000004(R0)", 0), 0);
+        assert_eq!(exec_reg("ADD R0, R0, R3", 3), 0);
+    }
+
+    #[test]
+    fn test_execute_load_store() {
+        assert_eq!(exec_reg("LDW R3, 0x00000008(R0)", 3), 9);
+        assert_eq!(exec_reg("LDW R3, 0x00000000(R1)", 3), 0);
+
+        let (machine, result) = exec("STW R2, 0x00000004(R0)");
+        assert_eq!(result, Ok(false));
+        assert_eq!(machine.memory()[1], -5i32 as u32);
+
+        let (machine, result) = exec("STW R1, 0x00000000(R1)");
+        assert_eq!(result, Ok(false));
+        assert_eq!(machine.memory()[3], 12);
+    }
+
+    #[test]
+    fn test_execute_invalid_memory_access() {
+        assert_eq!(
+            exec("LDW R3, 0x00000002(R0)").1,
+            Err(Fault::InvalidMemoryAccess(2))
+        );
+        assert_eq!(
+            exec("STW R3, 0x00000000(R2)").1,
+            Err(Fault::InvalidMemoryAccess(-5))
+        );
+        assert_eq!(
+            exec("LDW R3, 0x00001000(R0)").1,
+            Err(Fault::InvalidMemoryAccess(0x1000))
+        );
+    }
+
+    #[test]
+    fn test_execute_branches() {
+        // R0 = 0, R1 = 12, R2 = -5
+        let cases = [
+            ("BRZ", [true, false, false]),
+            ("BRNZ", [false, true, true]),
+            ("BRGT", [false, true, false]),
+            ("BRGE", [true, true, false]),
+            ("BRLT", [false, false, true]),
+            ("BRLE", [true, false, true]),
+        ];
+
+        for (opcode, expected) in cases {
+            for (reg, taken) in expected.iter().enumerate() {
+                let (_, result) = exec(&format!("{} R{}, l1", opcode, reg));
+                assert_eq!(result, Ok(*taken), "{} R{}", opcode, reg);
+            }
+        }
+    }
+
     #[test]
     fn test_valid_display() {
         assert_eq!(Opcode::NOP.to_string(), "NOP");
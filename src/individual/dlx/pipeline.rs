@@ -0,0 +1,404 @@
+use std::fmt;
+
+use super::{Instruction, Opcode, machine::REGISTER_COUNT};
+
+/// Number of stages in the pipeline (IF, ID, EX, MEM, WB)
+pub const PIPELINE_DEPTH: usize = 5;
+
+/// Cycles between ID and WB of an instruction.
+///
+/// Registers are written in the first half of WB and read in the second half of ID,
+/// so without forwarding a value can be read this many cycles after its producer was issued.
+const REGISTER_FILE_DISTANCE: usize = 3;
+
+/// Forwarding paths available in the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Forwarding {
+    /// EX/MEM -> EX, ALU result can be used by the next instruction.
+    pub ex_to_ex: bool,
+    /// MEM/WB -> EX, ALU and load results can be used by the instruction after next.
+    pub mem_to_ex: bool,
+}
+
+impl Forwarding {
+    /// No forwarding, every value goes through the register file
+    pub fn none() -> Self {
+        Forwarding::default()
+    }
+
+    /// Both forwarding paths enabled
+    pub fn full() -> Self {
+        Forwarding {
+            ex_to_ex: true,
+            mem_to_ex: true,
+        }
+    }
+}
+
+/// How branches are handled by the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BranchPolicy {
+    /// Number of instructions after a branch, that are always executed
+    pub delay_slots: usize,
+    /// Stall cycles after a taken branch (after its delay slots)
+    pub taken_penalty: usize,
+    /// Stall cycles after a not taken branch (after its delay slots)
+    pub not_taken_penalty: usize,
+}
+
+/// Configuration of the pipeline model.
+///
+/// The default configuration matches the reference emulator: no forwarding,
+/// two delay slots and no additional branch penalty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PipelineConfig {
+    pub forwarding: Forwarding,
+    pub branch: BranchPolicy,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        PipelineConfig {
+            forwarding: Forwarding::none(),
+            branch: BranchPolicy {
+                delay_slots: 2,
+                taken_penalty: 0,
+                not_taken_penalty: 0,
+            },
+        }
+    }
+}
+
+/// Timing statistics of a single instruction of the program, summed over all its executions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InstructionTiming {
+    /// How many times the instruction was executed
+    pub executions: usize,
+    /// Stall cycles waiting for an ALU result
+    pub data_stalls: usize,
+    /// Stall cycles waiting for a result of a load
+    pub load_use_stalls: usize,
+    /// Stall cycles caused by the branch penalty of this instruction
+    pub branch_stalls: usize,
+    /// Operands that were read through a forwarding path
+    pub forwards: usize,
+}
+
+impl InstructionTiming {
+    /// Returns all stall cycles caused by the instruction
+    pub fn stall_cycles(&self) -> usize {
+        self.data_stalls + self.load_use_stalls + self.branch_stalls
+    }
+}
+
+/// Timing report of a program run through the pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PipelineReport {
+    pub cycle_count: usize,
+    pub instruction_count: usize,
+    /// Timing of every instruction, indexed by the position in the program
+    pub instructions: Vec<InstructionTiming>,
+}
+
+impl PipelineReport {
+    pub fn data_stalls(&self) -> usize {
+        self.instructions.iter().map(|t| t.data_stalls).sum()
+    }
+
+    pub fn load_use_stalls(&self) -> usize {
+        self.instructions.iter().map(|t| t.load_use_stalls).sum()
+    }
+
+    pub fn branch_stalls(&self) -> usize {
+        self.instructions.iter().map(|t| t.branch_stalls).sum()
+    }
+
+    pub fn stall_cycles(&self) -> usize {
+        self.instructions.iter().map(|t| t.stall_cycles()).sum()
+    }
+}
+
+impl fmt::Display for PipelineReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "cycles: {}, instructions: {}, stalls: {} (data: {}, load-use: {}, branch: {})",
+            self.cycle_count,
+            self.instruction_count,
+            self.stall_cycles(),
+            self.data_stalls(),
+            self.load_use_stalls(),
+            self.branch_stalls()
+        )?;
+        for (i, timing) in self.instructions.iter().enumerate() {
+            if timing.executions == 0 {
+                continue;
+            }
+            writeln!(
+                f,
+                "{:>4}: executed {:>5}x, stalls {:>5} (data: {}, load-use: {}, branch: {}), forwards {}",
+                i,
+                timing.executions,
+                timing.stall_cycles(),
+                timing.data_stalls,
+                timing.load_use_stalls,
+                timing.branch_stalls,
+                timing.forwards
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Last writer of a register
+#[derive(Debug, Clone, Copy)]
+struct Producer {
+    issue: usize,
+    is_load: bool,
+}
+
+/// Cycle-accurate model of the 5-stage DLX pipeline (IF, ID, EX, MEM, WB).
+///
+/// Instructions are fed in execution order. The model keeps track of the cycle
+/// in which every instruction enters ID, stalling it until all of its operands
+/// are available, either from the register file or through a forwarding path.
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    config: PipelineConfig,
+    producers: [Option<Producer>; REGISTER_COUNT],
+    next_issue: usize,
+    report: PipelineReport,
+}
+
+impl Pipeline {
+    pub fn new(config: PipelineConfig) -> Self {
+        Pipeline {
+            config,
+            producers: [None; REGISTER_COUNT],
+            next_issue: 0,
+            report: PipelineReport::default(),
+        }
+    }
+
+    /// Getter for config
+    pub fn config(&self) -> &PipelineConfig {
+        &self.config
+    }
+
+    /// Returns the number of cycles taken so far.
+    ///
+    /// Includes filling the pipeline and the halt instruction fetched past the end of the program.
+    pub fn cycle_count(&self) -> usize {
+        self.next_issue + PIPELINE_DEPTH
+    }
+
+    /// Returns the timing report of all instructions issued so far
+    pub fn report(&self) -> PipelineReport {
+        PipelineReport {
+            cycle_count: self.cycle_count(),
+            ..self.report.clone()
+        }
+    }
+
+    fn timing_mut(&mut self, pc: usize) -> &mut InstructionTiming {
+        if self.report.instructions.len() <= pc {
+            self.report
+                .instructions
+                .resize(pc + 1, InstructionTiming::default());
+        }
+        &mut self.report.instructions[pc]
+    }
+
+    /// Returns the first cycle in which an operand written by `producer` can be used by an instruction in ID.
+    fn operand_ready(&self, producer: &Producer) -> usize {
+        let forwarding = self.config.forwarding;
+
+        let forwarded = if !producer.is_load && forwarding.ex_to_ex {
+            Some(producer.issue + 1)
+        } else {
+            forwarding.mem_to_ex.then_some(producer.issue + 2)
+        };
+
+        forwarded.unwrap_or(producer.issue + REGISTER_FILE_DISTANCE)
+    }
+
+    /// Issues the instruction at position `pc`, stalling it until its operands are available.
+    pub fn issue(&mut self, pc: usize, instr: &Instruction) {
+        let (sources, destination) = instr.get_register_usage();
+        let producers: Vec<Producer> = sources
+            .into_iter()
+            .filter_map(|reg| self.producers[reg])
+            .collect();
+
+        let mut issue = self.next_issue;
+        let mut load_use = false;
+
+        for producer in &producers {
+            let ready = self.operand_ready(producer);
+            if ready > issue {
+                issue = ready;
+                load_use = producer.is_load;
+            }
+        }
+
+        // Operands not yet written back to the register file had to be forwarded
+        let forwards = producers
+            .iter()
+            .filter(|producer| producer.issue + REGISTER_FILE_DISTANCE > issue)
+            .count();
+
+        let stalls = issue - self.next_issue;
+        self.next_issue = issue + 1;
+        self.report.instruction_count += 1;
+
+        if let Some(reg) = destination {
+            self.producers[reg] = Some(Producer {
+                issue,
+                is_load: instr.get_opcode() == &Opcode::LDW,
+            });
+        }
+
+        let timing = self.timing_mut(pc);
+        timing.executions += 1;
+        timing.forwards += forwards;
+        if load_use {
+            timing.load_use_stalls += stalls;
+        } else {
+            timing.data_stalls += stalls;
+        }
+    }
+
+    /// Accounts for the branch at position `pc` after its delay slots were issued.
+    pub fn resolve_branch(&mut self, pc: usize, taken: bool) {
+        let penalty = if taken {
+            self.config.branch.taken_penalty
+        } else {
+            self.config.branch.not_taken_penalty
+        };
+
+        self.next_issue += penalty;
+        self.timing_mut(pc).branch_stalls += penalty;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::individual::dlx::{Individual, Machine};
+
+    const SOI_CODE: &str = include_str!("../../../examples/short_code.txt");
+    const SOI_LONG_CODE: &str = include_str!("../../../examples/long_code.txt");
+
+    fn run(code: &str, config: PipelineConfig) -> (Machine, PipelineReport) {
+        let mut machine = Machine::with_pipeline(&[], config);
+        machine.run(&Individual::new(code).unwrap()).unwrap();
+        let report = machine.pipeline_report();
+        (machine, report)
+    }
+
+    fn forwarding(ex_to_ex: bool, mem_to_ex: bool) -> PipelineConfig {
+        PipelineConfig {
+            forwarding: Forwarding {
+                ex_to_ex,
+                mem_to_ex,
+            },
+            ..PipelineConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_no_hazards() {
+        let (_, report) = run(
+            "ADDI R0, 0x00000004, R1\nADDI R0, 0x00000008, R2",
+            PipelineConfig::default(),
+        );
+
+        assert_eq!(report.instruction_count, 2);
+        assert_eq!(report.stall_cycles(), 0);
+        assert_eq!(report.cycle_count, 2 + PIPELINE_DEPTH);
+    }
+
+    #[test]
+    fn test_data_hazard_stalls() {
+        let code = "ADDI R0, 0x00000004, R1\nADD R1, R1, R2";
+
+        let (_, report) = run(code, forwarding(false, false));
+        assert_eq!(report.instructions[1].data_stalls, 2);
+        assert_eq!(report.instructions[1].forwards, 0);
+
+        let (_, report) = run(code, forwarding(false, true));
+        assert_eq!(report.instructions[1].data_stalls, 1);
+        assert_eq!(report.instructions[1].forwards, 2);
+
+        let (_, report) = run(code, forwarding(true, true));
+        assert_eq!(report.instructions[1].data_stalls, 0);
+        assert_eq!(report.cycle_count, 2 + PIPELINE_DEPTH);
+    }
+
+    #[test]
+    fn test_load_use_stalls() {
+        let code = "LDW R1, 0x00000000(R0)\nADD R1, R1, R2";
+
+        let (_, report) = run(code, forwarding(true, true));
+        assert_eq!(report.instructions[1].load_use_stalls, 1);
+        assert_eq!(report.instructions[1].data_stalls, 0);
+
+        let (_, report) = run(code, forwarding(true, false));
+        assert_eq!(report.instructions[1].load_use_stalls, 2);
+    }
+
+    #[test]
+    fn test_branch_penalty() {
+        // Loop executed 4 times, branch taken 3 times
+        let code = "ADDI R0, 0x00000004, R1\nNOP\nNOP\nl1: SUBI R1, 0x00000001, R1\nNOP\nNOP\nBRNZ R1, l1\nNOP\nNOP";
+
+        let (_, base) = run(code, PipelineConfig::default());
+
+        let mut config = PipelineConfig::default();
+        config.branch.taken_penalty = 2;
+        config.branch.not_taken_penalty = 1;
+        let (_, report) = run(code, config);
+
+        assert_eq!(report.instructions[6].branch_stalls, 3 * 2 + 1);
+        assert_eq!(report.cycle_count, base.cycle_count + 3 * 2 + 1);
+    }
+
+    #[test]
+    fn test_delay_slots() {
+        let code = "BRZ R0, l1\nADDI R0, 0x00000001, R1\nl1: ADDI R1, 0x00000001, R1";
+
+        // Both delay slots are executed, including the branch target, which is then executed again
+        let (machine, _) = run(code, PipelineConfig::default());
+        assert_eq!(machine.register(1), 3);
+
+        let mut config = PipelineConfig::default();
+        config.branch.delay_slots = 0;
+        let (machine, _) = run(code, config);
+        assert_eq!(machine.register(1), 1);
+    }
+
+    #[test]
+    fn test_soi_stall_accounting() {
+        let (_, report) = run(SOI_CODE, PipelineConfig::default());
+
+        assert_eq!(
+            report.cycle_count,
+            report.instruction_count + report.stall_cycles() + PIPELINE_DEPTH
+        );
+        assert_eq!(report.branch_stalls(), 0);
+        assert!(report.stall_cycles() > 0);
+
+        let (_, forwarded) = run(SOI_CODE, forwarding(true, true));
+        assert_eq!(forwarded.instruction_count, report.instruction_count);
+        assert!(forwarded.cycle_count < report.cycle_count);
+    }
+
+    #[test]
+    fn test_padded_program_has_no_stalls() {
+        let (_, report) = run(SOI_LONG_CODE, PipelineConfig::default());
+
+        println!("{}", report);
+
+        assert_eq!(report.stall_cycles(), 0);
+    }
+}
@@ -1,9 +1,13 @@
 use std::fmt;
 
-use rand::Rng;
+use rand::{Rng, RngCore};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Represents the registers in the DLX architecture.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i8)]
 pub enum Register {
     R0 = 0,
@@ -47,15 +51,18 @@ impl fmt::Display for Register {
 }
 
 impl Register {
-    pub fn rand_up_to(n: usize) -> Option<Self> {
+    /// Returns the number of the register
+    pub fn index(&self) -> usize {
+        self.clone() as usize
+    }
+
+    pub fn rand_up_to(n: usize, rng: &mut dyn RngCore) -> Option<Self> {
         if n > 31 {
             return None;
         }
 
-        let mut rng = rand::rng();
-
         let index = rng.random_range(0..=n);
-        Some(unsafe { std::mem::transmute(index as i8) })
+        Some(unsafe { std::mem::transmute::<i8, Register>(index as i8) })
     }
 
     /// Parses a string to a Register enum.
@@ -0,0 +1,149 @@
+//! Sections of DLX programs, derived from their loops.
+//!
+//! Programs like SOI consist of a setup part followed by two nested loops, so they split into
+//! four sections:
+//!
+//! ```text
+//! Setup               instructions before the outer loop
+//! Outer Loop Top      from the start of the outer loop to the start of the inner loop
+//! Inner Loop          the inner loop, up to the delay slots of its closing branch
+//! Outer Loop Bottom   the rest of the outer loop and everything after it
+//! ```
+//!
+//! Loops are found from backward branches to labels. A program with a single loop has an empty
+//! Outer Loop Top, since the loop is both the inner and the outer one.
+
+use std::fmt;
+use std::ops::Range;
+
+use super::Individual;
+use super::pipeline::PipelineConfig;
+
+/// Kind of a program section, in program order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SectionKind {
+    Setup,
+    OuterLoopTop,
+    InnerLoop,
+    OuterLoopBottom,
+}
+
+impl SectionKind {
+    pub const ALL: [SectionKind; 4] = [
+        SectionKind::Setup,
+        SectionKind::OuterLoopTop,
+        SectionKind::InnerLoop,
+        SectionKind::OuterLoopBottom,
+    ];
+}
+
+impl fmt::Display for SectionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionKind::Setup => write!(f, "Setup"),
+            SectionKind::OuterLoopTop => write!(f, "Outer Loop Top"),
+            SectionKind::InnerLoop => write!(f, "Inner Loop"),
+            SectionKind::OuterLoopBottom => write!(f, "Outer Loop Bottom"),
+        }
+    }
+}
+
+/// Instruction ranges of the sections of a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sections {
+    ranges: [Range<usize>; 4],
+}
+
+impl Sections {
+    /// Finds the sections of a program. Returns `None` if the program has no loop.
+    pub fn find(indiv: &Individual) -> Option<Sections> {
+        let delay_slots = PipelineConfig::default().branch.delay_slots;
+        let instructions = indiv.instructions();
+
+        // Every backward branch closes a loop, which ends after its delay slots
+        let loops: Vec<Range<usize>> = instructions
+            .iter()
+            .enumerate()
+            .filter(|(_, instr)| instr.get_opcode().is_branch())
+            .filter_map(|(index, instr)| {
+                let target = indiv.get_label_position(instr.get_label().as_ref()?)?;
+                (target <= index).then(|| target..(index + 1 + delay_slots).min(instructions.len()))
+            })
+            .collect();
+
+        let outer = loops
+            .iter()
+            .min_by_key(|range| (range.start, usize::MAX - range.end))?
+            .clone();
+
+        let inner = loops
+            .iter()
+            .filter(|range| outer.start < range.start && range.end <= outer.end)
+            .max_by_key(|range| (range.len(), usize::MAX - range.start))
+            .unwrap_or(&outer)
+            .clone();
+
+        let program_end = indiv.last_nop_index().max(outer.end);
+
+        Some(Sections {
+            ranges: [
+                0..outer.start,
+                outer.start..inner.start,
+                inner.start..inner.end,
+                inner.end..program_end,
+            ],
+        })
+    }
+
+    /// Returns the instruction range of a section
+    pub fn range(&self, kind: SectionKind) -> Range<usize> {
+        self.ranges[kind as usize].clone()
+    }
+
+    /// Iterates over the sections in program order
+    pub fn iter(&self) -> impl Iterator<Item = (SectionKind, Range<usize>)> + '_ {
+        SectionKind::ALL
+            .iter()
+            .map(|&kind| (kind, self.range(kind)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOI_CODE: &str = include_str!("../../../examples/short_code.txt");
+
+    #[test]
+    fn test_soi_sections() {
+        let sections = Sections::find(&Individual::new(SOI_CODE).unwrap()).unwrap();
+
+        assert_eq!(sections.range(SectionKind::Setup), 0..10);
+        assert_eq!(sections.range(SectionKind::OuterLoopTop), 10..15);
+        assert_eq!(sections.range(SectionKind::InnerLoop), 15..28);
+        // The last delay slot of the outer loop is the first NOP of the padding
+        assert_eq!(sections.range(SectionKind::OuterLoopBottom), 28..41);
+    }
+
+    #[test]
+    fn test_single_loop_sections() {
+        let indiv = Individual::new(
+            "ADDI R0, 0x00000004, R1\nl1: SUBI R1, 0x00000001, R1\nBRNZ R1, l1\nNOP\nNOP\nSTW R1, 0x00000000(R0)",
+        )
+        .unwrap();
+        let sections = Sections::find(&indiv).unwrap();
+
+        assert_eq!(sections.range(SectionKind::Setup), 0..1);
+        assert_eq!(sections.range(SectionKind::OuterLoopTop), 1..1);
+        assert_eq!(sections.range(SectionKind::InnerLoop), 1..5);
+        assert_eq!(sections.range(SectionKind::OuterLoopBottom), 5..6);
+    }
+
+    #[test]
+    fn test_no_sections() {
+        assert!(
+            Sections::find(&Individual::new("ADD R1, R2, R3\nBRZ R1, h1\nh1: NOP").unwrap())
+                .is_none()
+        );
+    }
+}
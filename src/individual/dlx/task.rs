@@ -0,0 +1,957 @@
+//! Problems solved by DLX individuals.
+//!
+//! A task describes the memory image a program starts with, the test vectors it has to solve
+//! and how its results are turned into a fitness. Every test vector writes its own inputs on top
+//! of the task memory and expects its own output, so a program cannot simply hard-code the
+//! answer. Tasks are built in code or read from task files:
+//!
+//! ```text
+//! # Comments start with '#'
+//! name soi
+//! cycles 20000
+//! memory 0x2C0 1 1 1 1
+//! expect 0x300 0 0 0 0
+//! vector
+//! memory 0x200 1 2 3 4
+//! expect 0x300 1 3 6 10
+//! scoring graded
+//! weights wrong_output=1 per_cycle=1 correct_word=1 hamming=0.5 distance=0.5
+//! ```
+//!
+//! Addresses are byte addresses of words. Values are decimal or hex with a `0x` prefix.
+//! `memory` and `expect` can be repeated. `vector` starts a new test vector: `memory` lines
+//! after it are inputs of that vector, `memory` lines before the first `vector` are shared by
+//! all vectors. `expect` lines before the first `vector` belong to the first test vector.
+
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+use rand::Rng;
+
+use super::emu::EmulatorResult;
+use super::machine::MEMORY_SIZE;
+use crate::error::EvaluationError;
+
+const SOI_TASK: &str = include_str!("../../../tasks/soi.task");
+
+static SOI: LazyLock<Arc<Task>> =
+    LazyLock::new(|| Arc::new(Task::parse(SOI_TASK).expect("bundled SOI task is valid")));
+
+const SOI_INPUT_ADDR: usize = 0x200;
+const SOI_INPUT_SIZE: usize = 32;
+const SOI_OUTPUT_ADDR: usize = 0x300;
+const SOI_TAPS: usize = 16;
+
+/// Test vector of tasks without any, which expects nothing
+static EMPTY_VECTOR: TestVector = TestVector {
+    inputs: Vec::new(),
+    expected: Vec::new(),
+};
+
+/// Consecutive memory words starting at a byte address
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MemoryRegion {
+    pub address: usize,
+    pub values: Vec<u32>,
+}
+
+impl MemoryRegion {
+    /// Creates a region, checking that it is word aligned and fits in memory
+    fn new(address: usize, values: &[u32]) -> Result<Self, RegionError> {
+        let region = MemoryRegion {
+            address,
+            values: values.to_vec(),
+        };
+
+        if !address.is_multiple_of(4) {
+            return Err(RegionError::Unaligned(address));
+        }
+        if region.end() > MEMORY_SIZE {
+            return Err(RegionError::OutOfMemory {
+                address,
+                words: values.len(),
+            });
+        }
+
+        Ok(region)
+    }
+
+    /// Index of the first word of the region
+    fn start(&self) -> usize {
+        self.address / 4
+    }
+
+    fn end(&self) -> usize {
+        self.start() + self.values.len()
+    }
+
+    /// Returns true if the memory contains the values of the region
+    pub fn matches(&self, memory: &[u32]) -> bool {
+        memory.get(self.start()..self.end()) == Some(&self.values[..])
+    }
+
+    fn write_to(&self, memory: &mut [u32]) {
+        memory[self.start()..self.end()].copy_from_slice(&self.values);
+    }
+}
+
+/// Memory region which cannot be written to the memory of a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionError {
+    /// Byte address which is not a multiple of 4
+    Unaligned(usize),
+    /// Region going past the end of memory
+    OutOfMemory { address: usize, words: usize },
+}
+
+impl fmt::Display for RegionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionError::Unaligned(address) => {
+                write!(f, "address 0x{:X} is not word aligned", address)
+            }
+            RegionError::OutOfMemory { address, words } => write!(
+                f,
+                "region at 0x{:X} with {} words does not fit in memory",
+                address, words
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegionError {}
+
+/// Inputs written to memory before a run, together with the output expected after it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TestVector {
+    inputs: Vec<MemoryRegion>,
+    expected: Vec<MemoryRegion>,
+}
+
+impl TestVector {
+    pub fn new() -> Self {
+        TestVector::default()
+    }
+
+    /// Adds input values, starting at the given byte address.
+    ///
+    /// Fails if the region is not word aligned or does not fit in memory.
+    pub fn try_with_input(mut self, address: usize, values: &[u32]) -> Result<Self, RegionError> {
+        self.inputs.push(MemoryRegion::new(address, values)?);
+        Ok(self)
+    }
+
+    /// Adds values a correct program leaves in memory, starting at the given byte address.
+    ///
+    /// Fails if the region is not word aligned or does not fit in memory.
+    pub fn try_with_expected(
+        mut self,
+        address: usize,
+        values: &[u32],
+    ) -> Result<Self, RegionError> {
+        self.expected.push(MemoryRegion::new(address, values)?);
+        Ok(self)
+    }
+
+    /// Same as `try_with_input`, for regions known to be valid.
+    ///
+    /// ## Panics
+    /// If the region is not word aligned or does not fit in memory.
+    pub fn with_input(self, address: usize, values: &[u32]) -> Self {
+        self.try_with_input(address, values)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as `try_with_expected`, for regions known to be valid.
+    ///
+    /// ## Panics
+    /// If the region is not word aligned or does not fit in memory.
+    pub fn with_expected(self, address: usize, values: &[u32]) -> Self {
+        self.try_with_expected(address, values)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Getter for the input regions
+    pub fn inputs(&self) -> &[MemoryRegion] {
+        &self.inputs
+    }
+
+    /// Getter for the expected output regions
+    pub fn expected(&self) -> &[MemoryRegion] {
+        &self.expected
+    }
+
+    /// Returns true if the memory contains all expected regions
+    pub fn is_solved_by(&self, memory: &[u32]) -> bool {
+        self.expected.iter().all(|region| region.matches(memory))
+    }
+
+    /// Sum of the graded scores of all expected words. Words missing from memory score 0.
+    fn correctness(&self, memory: &[u32], weights: &FitnessWeights) -> f32 {
+        self.expected
+            .iter()
+            .flat_map(|region| {
+                region.values.iter().enumerate().map(|(offset, &expected)| {
+                    memory
+                        .get(region.start() + offset)
+                        .map_or(0.0, |&actual| weights.word_score(actual, expected))
+                })
+            })
+            .sum()
+    }
+
+    /// Graded score of a correct output
+    fn max_correctness(&self, weights: &FitnessWeights) -> f32 {
+        let word_count: usize = self.expected.iter().map(|region| region.values.len()).sum();
+        word_count as f32 * weights.correct_word
+    }
+}
+
+/// How an emulator result is turned into a fitness
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Scoring {
+    /// Wrong output gets a constant fitness, only correct programs are ranked by cycle count
+    #[default]
+    Cliff,
+    /// Wrong output is ranked by how close it is to the expected values, correct programs
+    /// get a bonus for every cycle under the budget on top of the full correctness score
+    Graded,
+}
+
+/// Weights used to turn an emulator result into a fitness
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitnessWeights {
+    /// Fitness of a program which finishes within the budget, but with wrong output
+    pub wrong_output: f32,
+    /// Fitness of a correct program for every cycle it finishes under the budget
+    pub per_cycle: f32,
+    /// Graded scoring: fitness of every correct output word
+    pub correct_word: f32,
+    /// Graded scoring: fitness of a wrong word, scaled by the fraction of matching bits
+    pub hamming: f32,
+    /// Graded scoring: fitness of a wrong word, scaled by `1 / (1 + |actual - expected|)`
+    pub distance: f32,
+}
+
+impl Default for FitnessWeights {
+    fn default() -> Self {
+        FitnessWeights {
+            wrong_output: 1.0,
+            per_cycle: 1.0,
+            correct_word: 1.0,
+            hamming: 0.5,
+            distance: 0.5,
+        }
+    }
+}
+
+/// Hashes the bits of every weight, with -0.0 counted as 0.0 so equal weights hash equally
+impl Hash for FitnessWeights {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for weight in [
+            self.wrong_output,
+            self.per_cycle,
+            self.correct_word,
+            self.hamming,
+            self.distance,
+        ] {
+            (weight + 0.0).to_bits().hash(state);
+        }
+    }
+}
+
+impl FitnessWeights {
+    /// Graded score of a single output word
+    fn word_score(&self, actual: u32, expected: u32) -> f32 {
+        if actual == expected {
+            return self.correct_word;
+        }
+
+        let matching_bits = (actual ^ expected).count_zeros() as f32 / u32::BITS as f32;
+        let distance = (actual as i32 as i64 - expected as i32 as i64).unsigned_abs() as f32;
+
+        self.hamming * matching_bits + self.distance / (1.0 + distance)
+    }
+}
+
+/// Reason for which a test vector was not solved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorFailure {
+    /// The program did not finish
+    Fault,
+    /// The program finished after the given number of cycles, over the budget
+    OverBudget(usize),
+    WrongOutput,
+}
+
+impl fmt::Display for VectorFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VectorFailure::Fault => write!(f, "program did not finish"),
+            VectorFailure::OverBudget(cycles) => write!(f, "over the cycle budget ({})", cycles),
+            VectorFailure::WrongOutput => write!(f, "wrong output"),
+        }
+    }
+}
+
+/// Fitness of a program together with its results on every test vector
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub fitness: f32,
+    /// Emulator result of every test vector
+    pub results: Vec<EmulatorResult>,
+    /// Indices of the unsolved test vectors, with the reason
+    pub failures: Vec<(usize, VectorFailure)>,
+}
+
+impl Evaluation {
+    /// Returns true if every test vector was solved
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Fitness {}, solved {} of {} test vectors",
+            self.fitness,
+            self.results.len() - self.failures.len(),
+            self.results.len()
+        )?;
+
+        for (vector, failure) in &self.failures {
+            write!(f, "\n  vector {}: {}", vector, failure)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Error in a task file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTaskError {
+    /// Line of the error, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseTaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseTaskError {}
+
+/// Problem solved by DLX individuals
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Task {
+    name: String,
+    memory: Vec<u32>,
+    vectors: Vec<TestVector>,
+    cycle_budget: usize,
+    scoring: Scoring,
+    weights: FitnessWeights,
+}
+
+impl Task {
+    /// Creates a task with empty memory, no test vectors and a budget of 20000 cycles
+    pub fn new(name: &str) -> Self {
+        Task {
+            name: name.to_string(),
+            memory: vec![0; MEMORY_SIZE],
+            vectors: vec![],
+            cycle_budget: 20000,
+            scoring: Scoring::default(),
+            weights: FitnessWeights::default(),
+        }
+    }
+
+    /// Returns the bundled SOI task, the one all DLX individuals solve by default
+    pub fn soi() -> Arc<Task> {
+        SOI.clone()
+    }
+
+    /// Returns the SOI task with `count` additional test vectors of random samples
+    pub fn soi_random(count: usize, rng: &mut impl Rng) -> Task {
+        Task::clone(&SOI).with_random_vectors(count, rng, |rng| {
+            let samples: Vec<u32> = (0..SOI_INPUT_SIZE)
+                .map(|_| rng.random_range(0..1000))
+                .collect();
+
+            TestVector::new()
+                .with_input(SOI_INPUT_ADDR, &samples)
+                .with_expected(SOI_OUTPUT_ADDR, &soi_reference(&samples))
+        })
+    }
+
+    /// Writes values to the memory shared by all test vectors, starting at the given byte address.
+    ///
+    /// Fails if the region is not word aligned or does not fit in memory.
+    pub fn try_with_memory(mut self, address: usize, values: &[u32]) -> Result<Self, RegionError> {
+        MemoryRegion::new(address, values)?.write_to(&mut self.memory);
+        Ok(self)
+    }
+
+    /// Adds values a correct program leaves in memory to the last test vector,
+    /// creating one if there is none.
+    ///
+    /// Fails if the region is not word aligned or does not fit in memory.
+    pub fn try_with_expected(
+        mut self,
+        address: usize,
+        values: &[u32],
+    ) -> Result<Self, RegionError> {
+        let vector = self.vectors.pop().unwrap_or_default();
+        self.vectors
+            .push(vector.try_with_expected(address, values)?);
+        Ok(self)
+    }
+
+    /// Same as `try_with_memory`, for regions known to be valid.
+    ///
+    /// ## Panics
+    /// If the region is not word aligned or does not fit in memory.
+    pub fn with_memory(self, address: usize, values: &[u32]) -> Self {
+        self.try_with_memory(address, values)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as `try_with_expected`, for regions known to be valid.
+    ///
+    /// ## Panics
+    /// If the region is not word aligned or does not fit in memory.
+    pub fn with_expected(self, address: usize, values: &[u32]) -> Self {
+        self.try_with_expected(address, values)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn with_vector(mut self, vector: TestVector) -> Self {
+        self.vectors.push(vector);
+        self
+    }
+
+    /// Adds `count` test vectors made by `generate`, which typically draws random inputs and
+    /// computes the expected output with a reference implementation
+    pub fn with_random_vectors<R: Rng>(
+        mut self,
+        count: usize,
+        rng: &mut R,
+        mut generate: impl FnMut(&mut R) -> TestVector,
+    ) -> Self {
+        self.vectors.extend((0..count).map(|_| generate(rng)));
+        self
+    }
+
+    /// Sets the number of cycles after which a program gets no fitness
+    pub fn with_cycle_budget(mut self, cycle_budget: usize) -> Self {
+        self.cycle_budget = cycle_budget;
+        self
+    }
+
+    pub fn with_scoring(mut self, scoring: Scoring) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    pub fn with_weights(mut self, weights: FitnessWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Getter for the memory image shared by all test vectors
+    pub fn memory(&self) -> &[u32] {
+        &self.memory
+    }
+
+    /// Returns the test vectors. A task without test vectors has a single one expecting nothing.
+    pub fn vectors(&self) -> &[TestVector] {
+        if self.vectors.is_empty() {
+            std::slice::from_ref(&EMPTY_VECTOR)
+        } else {
+            &self.vectors
+        }
+    }
+
+    /// Returns the initial memory image of every test vector
+    pub fn vector_memories(&self) -> Vec<Vec<u32>> {
+        self.vectors()
+            .iter()
+            .map(|vector| {
+                let mut memory = self.memory.clone();
+                for input in &vector.inputs {
+                    input.write_to(&mut memory);
+                }
+                memory
+            })
+            .collect()
+    }
+
+    pub fn cycle_budget(&self) -> usize {
+        self.cycle_budget
+    }
+
+    pub fn scoring(&self) -> Scoring {
+        self.scoring
+    }
+
+    pub fn weights(&self) -> FitnessWeights {
+        self.weights
+    }
+
+    /// Computes the fitness of the emulator results of every test vector, in order.
+    ///
+    /// A program which fails or goes over the cycle budget on any vector gets 0 with either
+    /// scoring. Cycles only count once all vectors are solved, using the slowest run. Graded
+    /// scoring averages the correctness over the vectors.
+    ///
+    /// Fails if the number of results does not match the number of test vectors.
+    pub fn evaluate(&self, results: Vec<EmulatorResult>) -> Result<Evaluation, EvaluationError> {
+        let vectors = self.vectors();
+        if results.len() != vectors.len() {
+            return Err(EvaluationError::ResultCount {
+                expected: vectors.len(),
+                found: results.len(),
+            });
+        }
+
+        let failures: Vec<(usize, VectorFailure)> = vectors
+            .iter()
+            .zip(&results)
+            .enumerate()
+            .filter_map(|(index, (vector, result))| {
+                let failure = match &result.memory {
+                    _ if !result.success => VectorFailure::Fault,
+                    _ if result.cycle_count > self.cycle_budget => {
+                        VectorFailure::OverBudget(result.cycle_count)
+                    }
+                    Some(memory) if vector.is_solved_by(memory) => return None,
+                    _ => VectorFailure::WrongOutput,
+                };
+                Some((index, failure))
+            })
+            .collect();
+
+        let finished = failures
+            .iter()
+            .all(|(_, failure)| *failure == VectorFailure::WrongOutput);
+        let solved = failures.is_empty();
+
+        let fitness = if !finished {
+            0.0
+        } else {
+            let max_cycles = results.iter().map(|r| r.cycle_count).max().unwrap_or(0);
+            let cycle_bonus = (self.cycle_budget - max_cycles) as f32 * self.weights.per_cycle;
+
+            let mean = |score: &dyn Fn(&TestVector, &[u32]) -> f32| {
+                vectors
+                    .iter()
+                    .zip(&results)
+                    .map(|(vector, result)| {
+                        score(vector, result.memory.as_deref().unwrap_or_default())
+                    })
+                    .sum::<f32>()
+                    / vectors.len() as f32
+            };
+
+            match self.scoring {
+                Scoring::Cliff if solved => cycle_bonus,
+                Scoring::Cliff => self.weights.wrong_output,
+                Scoring::Graded if solved => {
+                    self.weights.wrong_output
+                        + mean(&|vector, _| vector.max_correctness(&self.weights))
+                        + cycle_bonus
+                }
+                Scoring::Graded => {
+                    self.weights.wrong_output
+                        + mean(&|vector, memory| vector.correctness(memory, &self.weights))
+                }
+            }
+        };
+
+        Ok(Evaluation {
+            fitness,
+            results,
+            failures,
+        })
+    }
+
+    /// Computes the fitness of the emulator results of every test vector, in order
+    pub fn fitness(&self, results: Vec<EmulatorResult>) -> Result<f32, EvaluationError> {
+        Ok(self.evaluate(results)?.fitness)
+    }
+
+    /// Parses a task file
+    pub fn parse(input: &str) -> Result<Task, ParseTaskError> {
+        let mut task = Task::new("");
+        let mut in_vector = false;
+
+        for (index, line) in input.lines().enumerate() {
+            let error = |message: String| ParseTaskError {
+                line: index + 1,
+                message,
+            };
+
+            let mut parts = line.split('#').next().unwrap().split_whitespace();
+            let Some(keyword) = parts.next() else {
+                continue;
+            };
+            let args: Vec<&str> = parts.collect();
+
+            match keyword {
+                "name" => task.name = args.join(" "),
+                "cycles" => match args[..] {
+                    [cycles] => task.cycle_budget = parse_number(cycles).map_err(error)? as usize,
+                    _ => return Err(error("expected a single cycle count".to_string())),
+                },
+                "vector" => {
+                    task.vectors.push(TestVector::new());
+                    in_vector = true;
+                }
+                "memory" | "expect" => {
+                    let Some((address, values)) = args.split_first() else {
+                        return Err(error(format!("'{}' needs an address", keyword)));
+                    };
+                    let address = parse_number(address).map_err(error)? as usize;
+                    let values: Vec<u32> = values
+                        .iter()
+                        .map(|value| parse_number(value))
+                        .collect::<Result<_, _>>()
+                        .map_err(error)?;
+                    let region = MemoryRegion::new(address, &values)
+                        .map_err(|err| error(err.to_string()))?;
+
+                    if keyword == "memory" && !in_vector {
+                        region.write_to(&mut task.memory);
+                        continue;
+                    }
+
+                    if task.vectors.is_empty() {
+                        task.vectors.push(TestVector::new());
+                    }
+                    let vector = task.vectors.last_mut().unwrap();
+
+                    if keyword == "memory" {
+                        vector.inputs.push(region);
+                    } else {
+                        vector.expected.push(region);
+                    }
+                }
+                "scoring" => match args[..] {
+                    ["cliff"] => task.scoring = Scoring::Cliff,
+                    ["graded"] => task.scoring = Scoring::Graded,
+                    _ => return Err(error("expected 'cliff' or 'graded'".to_string())),
+                },
+                "weights" => {
+                    for arg in args {
+                        let (weight, value) = arg.split_once('=').ok_or_else(|| {
+                            error(format!("expected 'weight=value', got '{}'", arg))
+                        })?;
+                        let value: f32 = value
+                            .parse()
+                            .map_err(|_| error(format!("invalid weight '{}'", value)))?;
+
+                        match weight {
+                            "wrong_output" => task.weights.wrong_output = value,
+                            "per_cycle" => task.weights.per_cycle = value,
+                            "correct_word" => task.weights.correct_word = value,
+                            "hamming" => task.weights.hamming = value,
+                            "distance" => task.weights.distance = value,
+                            _ => return Err(error(format!("unknown weight '{}'", weight))),
+                        }
+                    }
+                }
+                _ => return Err(error(format!("unknown keyword '{}'", keyword))),
+            }
+        }
+
+        Ok(task)
+    }
+
+    /// Reads and parses a task file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Task, ParseTaskError> {
+        let input = fs::read_to_string(path).map_err(|err| ParseTaskError {
+            line: 0,
+            message: err.to_string(),
+        })?;
+        Task::parse(&input)
+    }
+}
+
+/// Reference implementation of SOI: every output is the sum of the sample and the 15 before it
+pub fn soi_reference(samples: &[u32]) -> Vec<u32> {
+    (0..samples.len())
+        .map(|i| {
+            samples[i.saturating_sub(SOI_TAPS - 1)..=i]
+                .iter()
+                .fold(0u32, |sum, &sample| sum.wrapping_add(sample))
+        })
+        .collect()
+}
+
+/// Parses a decimal or `0x` prefixed hex number
+fn parse_number(s: &str) -> Result<u32, String> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+    .map_err(|_| format!("invalid number '{}'", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::individual::dlx::emu;
+
+    fn result(cycle_count: usize, memory: Vec<u32>) -> EmulatorResult {
+        EmulatorResult {
+            success: true,
+            cycle_count,
+            unknown: false,
+            memory: Some(memory),
+        }
+    }
+
+    fn failed() -> EmulatorResult {
+        EmulatorResult {
+            success: false,
+            cycle_count: 0,
+            unknown: true,
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn test_soi_task() {
+        let task = Task::soi();
+
+        assert_eq!(task.name(), "soi");
+        assert_eq!(task.cycle_budget(), 20000);
+        assert_eq!(task.memory(), &emu::reference_memory()[..]);
+        assert_eq!(task.vectors().len(), 1);
+
+        let expected = task.vectors()[0].expected();
+        assert_eq!(expected.len(), 4);
+        assert_eq!(expected[0].address, 0x300);
+        assert_eq!(expected[3].values[7], 392);
+    }
+
+    #[test]
+    fn test_soi_reference() {
+        let samples: Vec<u32> = (1..=32).collect();
+        let expected: Vec<u32> = Task::soi().vectors()[0]
+            .expected()
+            .iter()
+            .flat_map(|region| region.values.clone())
+            .collect();
+
+        assert_eq!(soi_reference(&samples), expected);
+    }
+
+    #[test]
+    fn test_soi_random() {
+        let task = Task::soi_random(3, &mut rand::rng());
+        let memories = task.vector_memories();
+
+        assert_eq!(task.vectors().len(), 4);
+        assert_eq!(memories[0], emu::reference_memory());
+        // Samples change, coefficients do not
+        assert_ne!(
+            memories[1][0x200 / 4..0x280 / 4],
+            memories[0][0x200 / 4..0x280 / 4]
+        );
+        assert_eq!(
+            memories[1][0x2C0 / 4..0x300 / 4],
+            memories[0][0x2C0 / 4..0x300 / 4]
+        );
+    }
+
+    #[test]
+    fn test_task_builder() {
+        let task = Task::new("copy")
+            .with_memory(0x10, &[7, 8])
+            .with_expected(0x20, &[7, 8])
+            .with_cycle_budget(100);
+
+        assert_eq!(task.memory()[4..6], [7, 8]);
+        assert_eq!(
+            task,
+            Task::parse("name copy\ncycles 100\nmemory 0x10 7 8\nexpect 32 0x7 0x8").unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "not word aligned")]
+    fn test_task_builder_unaligned() {
+        Task::new("unaligned").with_memory(0x3, &[1]);
+    }
+
+    #[test]
+    fn test_task_builder_errors() {
+        assert_eq!(
+            Task::new("unaligned").try_with_memory(0x3, &[1]),
+            Err(RegionError::Unaligned(0x3))
+        );
+        assert_eq!(
+            Task::new("too long").try_with_expected(0x3FC, &[1, 2]),
+            Err(RegionError::OutOfMemory {
+                address: 0x3FC,
+                words: 2
+            })
+        );
+        assert!(TestVector::new().try_with_input(0x2, &[1]).is_err());
+        assert!(TestVector::new().try_with_expected(0x400, &[1]).is_err());
+
+        let vector = TestVector::new().try_with_input(0x3FC, &[1]).unwrap();
+        assert_eq!(vector.inputs()[0].address, 0x3FC);
+    }
+
+    #[test]
+    fn test_task_fitness() {
+        let task = Task::new("copy")
+            .with_expected(0x20, &[7, 8])
+            .with_cycle_budget(100)
+            .with_weights(FitnessWeights {
+                wrong_output: 2.0,
+                per_cycle: 0.5,
+                ..FitnessWeights::default()
+            });
+
+        let mut memory = vec![0; MEMORY_SIZE];
+        assert_eq!(task.fitness(vec![result(10, memory.clone())]).unwrap(), 2.0);
+
+        memory[8..10].copy_from_slice(&[7, 8]);
+        assert_eq!(
+            task.fitness(vec![result(10, memory.clone())]).unwrap(),
+            45.0
+        );
+        assert_eq!(task.fitness(vec![result(101, memory)]).unwrap(), 0.0);
+        assert_eq!(task.fitness(vec![failed()]).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_task_graded_fitness() {
+        let task = Task::new("copy")
+            .with_expected(0x20, &[7, 8])
+            .with_cycle_budget(100)
+            .with_scoring(Scoring::Graded);
+        let fitness = |output: [u32; 2], cycle_count| {
+            let mut memory = vec![0; MEMORY_SIZE];
+            memory[8..10].copy_from_slice(&output);
+            task.fitness(vec![result(cycle_count, memory)]).unwrap()
+        };
+
+        // More correct words and closer values are better
+        assert!(fitness([7, 0], 10) > fitness([0, 0], 10));
+        assert!(fitness([7, 9], 10) > fitness([7, 0], 10));
+        assert!(fitness([7, 9], 10) < fitness([7, 8], 99));
+        assert!(fitness([0, 0], 10) > 1.0);
+
+        // Cycles only matter once the output is correct
+        assert_eq!(fitness([7, 0], 10), fitness([7, 0], 90));
+        assert_eq!(fitness([7, 8], 10), 1.0 + 2.0 + 90.0);
+        assert_eq!(fitness([7, 8], 101), 0.0);
+    }
+
+    #[test]
+    fn test_task_vectors() {
+        let task = Task::new("double")
+            .with_cycle_budget(100)
+            .with_vector(
+                TestVector::new()
+                    .with_input(0x0, &[1])
+                    .with_expected(0x4, &[2]),
+            )
+            .with_vector(
+                TestVector::new()
+                    .with_input(0x0, &[5])
+                    .with_expected(0x4, &[10]),
+            );
+        let output = |value: u32| {
+            let mut memory = vec![0; MEMORY_SIZE];
+            memory[1] = value;
+            memory
+        };
+
+        assert_eq!(task.vector_memories()[1][0], 5);
+
+        let evaluation = task
+            .evaluate(vec![result(10, output(2)), result(20, output(10))])
+            .unwrap();
+        assert!(evaluation.passed());
+        assert_eq!(evaluation.fitness, 80.0);
+
+        // Hard-coding the output of the first vector does not solve the second one
+        let evaluation = task
+            .evaluate(vec![result(10, output(2)), result(10, output(2))])
+            .unwrap();
+        assert_eq!(evaluation.failures, vec![(1, VectorFailure::WrongOutput)]);
+        assert_eq!(evaluation.fitness, 1.0);
+
+        let evaluation = task
+            .evaluate(vec![result(10, output(2)), failed()])
+            .unwrap();
+        assert_eq!(evaluation.failures, vec![(1, VectorFailure::Fault)]);
+        assert_eq!(evaluation.fitness, 0.0);
+        assert_eq!(
+            evaluation.to_string(),
+            "Fitness 0, solved 1 of 2 test vectors\n  vector 1: program did not finish"
+        );
+
+        assert_eq!(
+            task.evaluate(vec![result(10, output(2))]).unwrap_err(),
+            EvaluationError::ResultCount {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_task_parse_vectors() {
+        let task = Task::parse(
+            "memory 0x8 3\nexpect 0x4 2\nvector\nmemory 0x0 5\nexpect 0x4 10\nvector\nexpect 0x4 0",
+        )
+        .unwrap();
+
+        assert_eq!(task.vectors().len(), 3);
+        assert_eq!(task.vectors()[0].inputs(), []);
+        assert_eq!(task.vectors()[1].inputs()[0].values, vec![5]);
+        assert_eq!(task.vector_memories()[1][..3], [5, 0, 3]);
+        assert_eq!(task.vectors()[2].expected()[0].values, vec![0]);
+    }
+
+    #[test]
+    fn test_task_parse_scoring() {
+        let task = Task::parse("scoring graded\nweights hamming=0.25 correct_word=2").unwrap();
+
+        assert_eq!(task.scoring(), Scoring::Graded);
+        assert_eq!(task.weights().hamming, 0.25);
+        assert_eq!(task.weights().correct_word, 2.0);
+        assert_eq!(Task::soi().scoring(), Scoring::Cliff);
+        assert!(Task::parse("scoring steep").is_err());
+    }
+
+    #[test]
+    fn test_task_parse_errors() {
+        let err = Task::parse("name bad\n\nfoo 1").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.message, "unknown keyword 'foo'");
+
+        assert!(Task::parse("cycles many").is_err());
+        assert!(Task::parse("memory").is_err());
+        assert!(Task::parse("memory 0x2 1").is_err());
+        assert!(Task::parse("expect 0x3FC 1 2").is_err());
+        assert!(Task::parse("weights per_cycle").is_err());
+        assert!(Task::parse("weights speed=1").is_err());
+    }
+}
@@ -0,0 +1,449 @@
+//! Long-lived emulator worker processes.
+//!
+//! Workers read requests from stdin and write responses to stdout, both framed by a header line
+//! with the number of lines that follow:
+//!
+//! ```text
+//! RUN <program lines> <memory words> <cycle budget>
+//! <program, one instruction per line>
+//! <memory words in hex, separated by spaces; omitted if there are none>
+//!
+//! RESULT <output lines>
+//! <emulator output, in the format printed by interface.py>
+//! ```
+//!
+//! Programs going over the cycle budget of their request fail. Workers which cannot run
+//! a request answer with a single `ERROR <message>` line instead.
+//!
+//! The `emu_worker` binary is a worker backed by the native emulator, `src/emu_worker.py` is one
+//! backed by the Python reference emulator.
+
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::Individual;
+use super::backend::{self, EmulatorBackend};
+use super::emu::{self, EmulatorResult};
+use crate::error::EmulatorError;
+
+/// Time a worker gets to answer a single request
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Program, initial memory and cycle budget sent to a worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerRequest {
+    pub program: String,
+    pub memory: Vec<u32>,
+    pub cycle_budget: usize,
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads a line without the line terminator. Returns `None` at the end of input.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end_matches(['\n', '\r']).to_string()))
+}
+
+/// Reads a line, treating the end of input as an error
+fn expect_line(reader: &mut impl BufRead) -> io::Result<String> {
+    read_line(reader)?.ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))
+}
+
+/// Writes a request to a worker
+pub fn write_request(
+    writer: &mut impl Write,
+    program: &str,
+    memory: &[u32],
+    cycle_budget: usize,
+) -> io::Result<()> {
+    let lines: Vec<&str> = program.lines().collect();
+    writeln!(
+        writer,
+        "RUN {} {} {}",
+        lines.len(),
+        memory.len(),
+        cycle_budget
+    )?;
+
+    for line in lines {
+        writeln!(writer, "{}", line)?;
+    }
+
+    if !memory.is_empty() {
+        let memory_hex: Vec<String> = memory.iter().map(|word| format!("{:08X}", word)).collect();
+        writeln!(writer, "{}", memory_hex.join(" "))?;
+    }
+
+    writer.flush()
+}
+
+/// Reads a request on the worker side. Returns `None` once the input is closed.
+pub fn read_request(reader: &mut impl BufRead) -> io::Result<Option<WorkerRequest>> {
+    let Some(header) = read_line(reader)? else {
+        return Ok(None);
+    };
+
+    let counts: Vec<usize> = match header.split_whitespace().collect::<Vec<_>>()[..] {
+        ["RUN", lines, words, budget] => [lines, words, budget]
+            .iter()
+            .map(|count| count.parse().map_err(|_| invalid_data(header.clone())))
+            .collect::<io::Result<_>>()?,
+        _ => return Err(invalid_data(format!("invalid request header '{}'", header))),
+    };
+
+    let mut program = String::new();
+    for _ in 0..counts[0] {
+        program.push_str(&expect_line(reader)?);
+        program.push('\n');
+    }
+
+    let memory = if counts[1] > 0 {
+        let memory_line = expect_line(reader)?;
+        let memory: Vec<u32> = memory_line
+            .split_whitespace()
+            .map(|hex| u32::from_str_radix(hex, 16))
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid_data(format!("invalid memory line '{}'", memory_line)))?;
+
+        if memory.len() != counts[1] {
+            return Err(invalid_data(format!(
+                "expected {} memory words, got {}",
+                counts[1],
+                memory.len()
+            )));
+        }
+        memory
+    } else {
+        vec![]
+    };
+
+    Ok(Some(WorkerRequest {
+        program,
+        memory,
+        cycle_budget: counts[2],
+    }))
+}
+
+/// Writes a response on the worker side
+pub fn write_response(writer: &mut impl Write, result: &EmulatorResult) -> io::Result<()> {
+    let output = emu::format_emu_output(result);
+    let lines: Vec<&str> = output.lines().collect();
+
+    writeln!(writer, "RESULT {}", lines.len())?;
+    for line in lines {
+        writeln!(writer, "{}", line)?;
+    }
+
+    writer.flush()
+}
+
+/// Command used to start a worker process
+#[derive(Debug, Clone, Hash)]
+pub struct WorkerCommand {
+    program: PathBuf,
+    args: Vec<String>,
+    current_dir: Option<PathBuf>,
+}
+
+impl WorkerCommand {
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        WorkerCommand {
+            program: program.into(),
+            args: vec![],
+            current_dir: None,
+        }
+    }
+
+    /// Command starting `src/emu_worker.py`, a worker running the Python reference emulator
+    /// from the `src/emulator` submodule.
+    ///
+    /// Like `run_python_emulator`, paths are relative to the crate root.
+    pub fn python() -> Self {
+        WorkerCommand::new("python3")
+            .arg("-B")
+            .arg("-u")
+            .arg("src/emu_worker.py")
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    fn spawn(&self) -> io::Result<Worker> {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+
+        let mut child = command.spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        // Responses are read on a separate thread, so waiting for them can time out
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Worker {
+            child,
+            stdin: BufWriter::new(stdin),
+            lines,
+        })
+    }
+}
+
+/// Running worker process
+#[derive(Debug)]
+struct Worker {
+    child: Child,
+    stdin: BufWriter<ChildStdin>,
+    lines: Receiver<String>,
+}
+
+impl Worker {
+    fn request(
+        &mut self,
+        program: &str,
+        memory: &[u32],
+        cycle_budget: usize,
+        timeout: Duration,
+    ) -> Result<EmulatorResult, EmulatorError> {
+        write_request(&mut self.stdin, program, memory, cycle_budget).map_err(EmulatorError::Io)?;
+
+        let deadline = Instant::now() + timeout;
+
+        let header = self.receive(deadline)?;
+        if let Some(message) = header.strip_prefix("ERROR ") {
+            return Err(EmulatorError::Failed(message.to_string()));
+        }
+
+        let line_count: usize = header
+            .strip_prefix("RESULT ")
+            .and_then(|count| count.trim().parse().ok())
+            .ok_or_else(|| {
+                EmulatorError::InvalidOutput(format!("invalid response header '{}'", header))
+            })?;
+
+        let mut output = vec![];
+        for _ in 0..line_count {
+            output.push(self.receive(deadline)?);
+        }
+
+        emu::parse_emu_output(&output.join("\n"))
+    }
+
+    fn receive(&self, deadline: Instant) -> Result<String, EmulatorError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+
+        self.lines.recv_timeout(timeout).map_err(|err| match err {
+            RecvTimeoutError::Timeout => EmulatorError::Timeout,
+            RecvTimeoutError::Disconnected => EmulatorError::Exited,
+        })
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Pool of long-lived emulator worker processes.
+///
+/// Workers are started on first use. A worker that crashes, breaks the protocol or does not
+/// answer in time is killed and replaced by a new one on the next request.
+#[derive(Debug)]
+pub struct WorkerPool {
+    command: WorkerCommand,
+    timeout: Duration,
+    workers: Vec<Mutex<Option<Worker>>>,
+    restarts: AtomicUsize,
+}
+
+impl WorkerPool {
+    /// Creates a pool with one worker per rayon thread
+    pub fn new(command: WorkerCommand) -> Self {
+        WorkerPool::with_size(command, rayon::current_num_threads())
+    }
+
+    /// Creates a pool with the given number of workers
+    pub fn with_size(command: WorkerCommand, size: usize) -> Self {
+        WorkerPool {
+            command,
+            timeout: DEFAULT_TIMEOUT,
+            workers: (0..size.max(1)).map(|_| Mutex::new(None)).collect(),
+            restarts: AtomicUsize::new(0),
+        }
+    }
+
+    /// Sets the time a worker gets to answer a single request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the number of workers
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Returns how many workers had to be replaced
+    pub fn restarts(&self) -> usize {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    /// Locks a free worker slot, preferring the one belonging to the current rayon thread
+    fn acquire(&self) -> MutexGuard<'_, Option<Worker>> {
+        let start = rayon::current_thread_index().unwrap_or(0) % self.workers.len();
+
+        (0..self.workers.len())
+            .map(|offset| &self.workers[(start + offset) % self.workers.len()])
+            .find_map(|worker| worker.try_lock().ok())
+            .unwrap_or_else(|| {
+                self.workers[start]
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+            })
+    }
+
+    fn request(
+        &self,
+        slot: &mut Option<Worker>,
+        program: &str,
+        memory: &[u32],
+        cycle_budget: usize,
+    ) -> Result<EmulatorResult, EmulatorError> {
+        let worker = match slot {
+            Some(worker) => worker,
+            None => slot.insert(self.command.spawn().map_err(EmulatorError::Io)?),
+        };
+
+        let result = worker.request(program, memory, cycle_budget, self.timeout);
+        // A worker which answered with an error can take the next request, after any other
+        // error it is in an unknown state, so it is replaced on the next request
+        if result
+            .as_ref()
+            .is_err_and(|err| !matches!(err, EmulatorError::Failed(_)))
+        {
+            *slot = None;
+            self.restarts.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+impl EmulatorBackend for WorkerPool {
+    /// Runs the program on a worker, within the cycle budget of its task. Workers which exit
+    /// or fail to communicate are restarted and the program is retried once.
+    fn run(&self, program: &Individual, memory: &[u32]) -> Result<EmulatorResult, EmulatorError> {
+        let cycle_budget = program.task().cycle_budget();
+        let program = program.to_string();
+        let mut slot = self.acquire();
+
+        match self.request(&mut slot, &program, memory, cycle_budget) {
+            // A worker which died may have been killed by something other than this program,
+            // so it gets a second chance on a fresh worker
+            Err(EmulatorError::Exited | EmulatorError::Io(_)) => {
+                self.request(&mut slot, &program, memory, cycle_budget)
+            }
+            result => result,
+        }
+    }
+
+    /// Workers started by the same command give the same results
+    fn cache_key(&self) -> Option<u64> {
+        Some(backend::backend_key("worker", &self.command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_request_roundtrip() {
+        let program = "l1: ADDI R0, 0xFFFFFFFC, R1\nBRNZ R1, l1\n";
+        let mut buffer = vec![];
+        write_request(&mut buffer, program, &[1, 0xDEADBEEF], 20000).unwrap();
+        write_request(&mut buffer, "NOP", &[], 10).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        let request = read_request(&mut reader).unwrap().unwrap();
+        assert_eq!(request.program, program);
+        assert_eq!(request.memory, vec![1, 0xDEADBEEF]);
+        assert_eq!(request.cycle_budget, 20000);
+
+        let request = read_request(&mut reader).unwrap().unwrap();
+        assert_eq!(request.program, "NOP\n");
+        assert!(request.memory.is_empty());
+        assert_eq!(request.cycle_budget, 10);
+
+        assert!(read_request(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_request_invalid() {
+        let mut reader = Cursor::new("RUN 2 0 100\nNOP\n");
+        assert!(read_request(&mut reader).is_err());
+
+        let mut reader = Cursor::new("STOP\n");
+        assert!(read_request(&mut reader).is_err());
+
+        // requests without a cycle budget are refused
+        let mut reader = Cursor::new("RUN 1 0\nNOP\n");
+        assert!(read_request(&mut reader).is_err());
+
+        let mut reader = Cursor::new("RUN 0 2 100\n00000001\n");
+        assert!(read_request(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_response_format() {
+        let result = EmulatorResult {
+            success: true,
+            cycle_count: 42,
+            unknown: true,
+            memory: Some(vec![1, 2]),
+        };
+        let mut buffer = vec![];
+        write_response(&mut buffer, &result).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "RESULT 4\nCompleted\n42\nTrue\n00000001 00000002\n"
+        );
+    }
+}
@@ -1,7 +1,42 @@
+use rand::RngCore;
+
+use crate::error::Error;
+
 /// Generic trait for genetic algorithms individual
+///
+/// All randomness comes from the passed generator, so seeded runs are reproducible.
 pub trait Genetic {
     fn fitness(&self) -> f32;
-    fn generate() -> Self;
-    fn crossover(&self, other: &Self) -> Self;
-    fn mutate(&mut self) -> ();
+    fn generate(rng: &mut dyn RngCore) -> Self;
+    fn crossover(&self, other: &Self, rng: &mut dyn RngCore) -> Self;
+    fn mutate(&mut self, rng: &mut dyn RngCore);
+
+    /// Fitness, or the error which prevented computing it.
+    ///
+    /// `GenAlg` evaluates individuals through this method. Failed evaluations score 0,
+    /// are not cached and are counted in `GenerationStats::evaluation_errors`.
+    fn try_fitness(&self) -> Result<f32, Error> {
+        Ok(self.fitness())
+    }
+
+    /// Hash of everything the fitness depends on, used as key of the fitness cache.
+    ///
+    /// Individuals returning `None` are always evaluated.
+    fn cache_key(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Distance between two genomes, used to measure the diversity of a population
+pub trait Distance {
+    /// Non-negative distance, 0 for equal genomes
+    fn distance(&self, other: &Self) -> f32;
+}
+
+/// Individual optimized for several objectives at once, see `GenAlg::run_multi_objective`
+pub trait MultiObjective: Genetic {
+    /// Value of every objective, all of them are maximized.
+    ///
+    /// Every individual has to return the same number of objectives.
+    fn objectives(&self) -> Vec<f32>;
 }
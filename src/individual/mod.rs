@@ -1,4 +1,4 @@
 pub mod dlx;
 pub mod genetic;
 
-pub use genetic::Genetic;
+pub use genetic::{Distance, Genetic, MultiObjective};
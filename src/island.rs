@@ -0,0 +1,525 @@
+use std::time::{Duration, Instant};
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+use crate::config::{ConfigError, GenAlgConfig};
+use crate::error::Error;
+use crate::genalg::{FitnessIndiv, GenAlg, Generations, RunResult};
+use crate::individual::genetic::Genetic;
+
+/// Which islands receive the migrants of an island
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Topology {
+    /// Every island sends to the next one, the last one to the first
+    #[default]
+    Ring,
+    /// Every island sends to all other islands
+    FullyConnected,
+    /// Every island sends to another island chosen at random on every migration
+    Random,
+}
+
+/// Which individuals leave an island
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MigrantSelection {
+    /// The best individuals of the population
+    #[default]
+    Best,
+    /// Individuals chosen at random, without repetition
+    Random,
+}
+
+/// Which individuals of the receiving island are replaced by migrants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Replacement {
+    /// The worst individuals of the population
+    #[default]
+    Worst,
+    /// Individuals chosen at random, except the elite
+    Random,
+}
+
+/// How and how often individuals move between islands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    interval: usize,
+    migrants: usize,
+    topology: Topology,
+    selection: MigrantSelection,
+    replacement: Replacement,
+}
+
+impl Default for Migration {
+    fn default() -> Self {
+        Migration {
+            interval: 10,
+            migrants: 2,
+            topology: Topology::default(),
+            selection: MigrantSelection::default(),
+            replacement: Replacement::default(),
+        }
+    }
+}
+
+impl Migration {
+    /// Sets the number of generations between migrations, at least 1
+    pub fn with_interval(mut self, interval: usize) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the number of individuals every island sends on a migration
+    pub fn with_migrants(mut self, migrants: usize) -> Self {
+        self.migrants = migrants;
+        self
+    }
+
+    /// Sets which islands receive the migrants of an island
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Sets which individuals leave an island
+    pub fn with_selection(mut self, selection: MigrantSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets which individuals are replaced by migrants
+    pub fn with_replacement(mut self, replacement: Replacement) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    /// Getter for number of generations between migrations
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// Getter for number of individuals every island sends on a migration
+    pub fn migrants(&self) -> usize {
+        self.migrants
+    }
+
+    /// Getter for migration topology
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Getter for migrant selection policy
+    pub fn selection(&self) -> MigrantSelection {
+        self.selection
+    }
+
+    /// Getter for replacement policy
+    pub fn replacement(&self) -> Replacement {
+        self.replacement
+    }
+
+    /// Checks the migration parameters
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.interval == 0 {
+            return Err(ConfigError::ZeroMigrationInterval);
+        }
+
+        Ok(())
+    }
+
+    /// Picks the migrants leaving the sorted `population`
+    fn select_migrants<T: Genetic + Clone + Send + Sync + PartialEq + Eq>(
+        &self,
+        population: &[FitnessIndiv<T>],
+        rng: &mut dyn RngCore,
+    ) -> Vec<FitnessIndiv<T>> {
+        let count = self.migrants.min(population.len());
+
+        match self.selection {
+            MigrantSelection::Best => population[..count].to_vec(),
+            MigrantSelection::Random => rand::seq::index::sample(rng, population.len(), count)
+                .into_iter()
+                .map(|index| population[index].clone())
+                .collect(),
+        }
+    }
+
+    /// Islands receiving the migrants of island `source` out of `island_count`
+    fn destinations(
+        &self,
+        source: usize,
+        island_count: usize,
+        rng: &mut dyn RngCore,
+    ) -> Vec<usize> {
+        match self.topology {
+            Topology::Ring => vec![(source + 1) % island_count],
+            Topology::FullyConnected => (0..island_count).filter(|&i| i != source).collect(),
+            Topology::Random => {
+                // skips the source island
+                let destination = rng.random_range(0..island_count - 1);
+                vec![destination + usize::from(destination >= source)]
+            }
+        }
+    }
+}
+
+/// Outcome of an island model run
+#[derive(Clone, Debug)]
+pub struct IslandResult<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    /// Result of every island, in the order of the islands
+    pub islands: Vec<RunResult<T>>,
+    /// Best individual of all islands
+    pub best: FitnessIndiv<T>,
+    /// Migrations done during the run
+    pub migrations: usize,
+    pub elapsed: Duration,
+}
+
+/// Several `GenAlg` populations evolving in parallel, exchanging individuals periodically.
+///
+/// Islands evolve independently with their own configuration, selection, stop conditions
+/// and observers. Every `Migration::interval` generations migrants are copied between them,
+/// which keeps the islands from converging to the same solution too early.
+pub struct IslandModel<T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    islands: Vec<GenAlg<T>>,
+    migration: Migration,
+    /// Chooses migrants, destinations and replaced individuals
+    rng: ChaCha8Rng,
+}
+
+impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> IslandModel<T> {
+    /// Creates an island model from islands, which can be configured differently
+    pub fn new(islands: Vec<GenAlg<T>>) -> Self {
+        IslandModel {
+            islands,
+            migration: Migration::default(),
+            rng: ChaCha8Rng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    /// Creates `island_count` islands with random populations of `population_size`.
+    ///
+    /// A configuration with a seed gives every island its own seed derived from it,
+    /// and makes migrations reproducible.
+    pub fn from_config(island_count: usize, population_size: usize, config: GenAlgConfig) -> Self {
+        let mut rng = match config.seed() {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_rng(&mut rand::rng()),
+        };
+
+        let islands = (0..island_count)
+            .map(|_| {
+                let island_config = match config.seed() {
+                    Some(_) => config.reseeded(rng.random()),
+                    None => config.clone(),
+                };
+                GenAlg::from_config(population_size, None, island_config)
+            })
+            .collect();
+
+        IslandModel {
+            islands,
+            migration: Migration::default(),
+            rng,
+        }
+    }
+
+    /// Sets how and how often individuals move between islands
+    pub fn with_migration(mut self, migration: Migration) -> Self {
+        self.migration = migration;
+        self
+    }
+
+    /// Sets the seed of migrations, islands are seeded by their own configuration
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Getter for islands
+    pub fn islands(&self) -> &[GenAlg<T>] {
+        &self.islands
+    }
+
+    /// Getter for migration parameters
+    pub fn migration(&self) -> &Migration {
+        &self.migration
+    }
+
+    /// Runs every island until it stops, like `GenAlg::run_genetic_algorithm`,
+    /// migrating between the islands which are still running.
+    ///
+    /// Islands run in parallel on the global rayon thread pool, each of them evaluates
+    /// on the thread pool of its own configuration.
+    /// ## Returns
+    /// * `Result<IslandResult<T>, Error>` - result of every island and the best individual,
+    ///   or the first error of an island
+    pub fn run(&mut self) -> Result<IslandResult<T>, Error> {
+        self.migration.validate()?;
+        if self.islands.is_empty() {
+            return Err(ConfigError::NoIslands.into());
+        }
+
+        let start_timer = Instant::now();
+        let interval = self.migration.interval;
+        let mut migrations = 0;
+        let mut runs: Vec<Generations<'_, T>> = self
+            .islands
+            .iter_mut()
+            .map(|island| island.generations())
+            .collect();
+
+        loop {
+            let finished = runs
+                .par_iter_mut()
+                .map(|run| {
+                    for _ in 0..interval {
+                        match run.next() {
+                            Some(summary) => summary.map(|_| ())?,
+                            None => return Ok(true),
+                        }
+                    }
+                    Ok(run.finish_if_stopped())
+                })
+                .collect::<Result<Vec<bool>, Error>>()?;
+
+            if finished.iter().all(|&finished| finished) {
+                break;
+            }
+
+            migrate(&self.migration, &mut self.rng, &mut runs, &finished);
+            migrations += 1;
+        }
+
+        let islands: Vec<RunResult<T>> = runs
+            .into_iter()
+            .map(|run| run.into_result().expect("finished run has a result"))
+            .collect();
+        let best = islands
+            .iter()
+            .map(|result| &result.best)
+            .max_by(|a, b| a.fitness().total_cmp(&b.fitness()))
+            .unwrap()
+            .clone();
+
+        Ok(IslandResult {
+            islands,
+            best,
+            migrations,
+            elapsed: start_timer.elapsed(),
+        })
+    }
+}
+
+/// Copies migrants between the islands, finished islands only send them
+fn migrate<T: Genetic + Clone + Send + Sync + PartialEq + Eq>(
+    migration: &Migration,
+    rng: &mut dyn RngCore,
+    runs: &mut [Generations<'_, T>],
+    finished: &[bool],
+) {
+    let island_count = runs.len();
+    if island_count < 2 {
+        return;
+    }
+
+    // collected first, so migrants do not travel further in the same migration
+    let mut incoming: Vec<Vec<FitnessIndiv<T>>> = vec![Vec::new(); island_count];
+    for (source, run) in runs.iter().enumerate() {
+        let migrants = migration.select_migrants(run.gen_alg().current_population(), rng);
+        for destination in migration.destinations(source, island_count, rng) {
+            incoming[destination].extend(migrants.iter().cloned());
+        }
+    }
+
+    for ((run, migrants), &finished) in runs.iter_mut().zip(incoming).zip(finished) {
+        if !finished {
+            run.gen_alg_mut()
+                .receive_migrants(migrants, migration.replacement, rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::termination::StopCondition;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Target {
+        value: i32,
+    }
+
+    impl Genetic for Target {
+        fn generate(rng: &mut dyn RngCore) -> Self {
+            Target {
+                value: rng.random_range(0..1000),
+            }
+        }
+
+        fn fitness(&self) -> f32 {
+            1000.0 - (self.value - 500).abs() as f32
+        }
+
+        fn crossover(&self, other: &Self, _rng: &mut dyn RngCore) -> Self {
+            Target {
+                value: (self.value + other.value) / 2,
+            }
+        }
+
+        fn mutate(&mut self, rng: &mut dyn RngCore) {
+            self.value += rng.random_range(-10..=10);
+        }
+    }
+
+    fn config(generations: usize) -> GenAlgConfig {
+        GenAlgConfig::builder()
+            .with_generations(generations)
+            .with_mutation_rate(0.0)
+            .with_seed(5)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_invalid_island_model() {
+        let mut empty = IslandModel::<Target>::new(Vec::new());
+        assert!(matches!(
+            empty.run(),
+            Err(Error::Config(ConfigError::NoIslands))
+        ));
+
+        let mut model = IslandModel::<Target>::from_config(2, 10, config(5))
+            .with_migration(Migration::default().with_interval(0));
+        assert!(matches!(
+            model.run(),
+            Err(Error::Config(ConfigError::ZeroMigrationInterval))
+        ));
+    }
+
+    #[test]
+    fn test_migration_spreads_best() {
+        let good = vec![Target { value: 500 }; 10];
+        let bad = vec![Target { value: 0 }; 10];
+        let islands = vec![
+            GenAlg::from_config(10, Some(&good), config(3)),
+            GenAlg::from_config(10, Some(&bad), config(3)),
+        ];
+
+        let result = IslandModel::new(islands)
+            .with_migration(Migration::default().with_interval(1).with_migrants(1))
+            .with_seed(1)
+            .run()
+            .unwrap();
+
+        // without mutation the bad island cannot improve on its own
+        assert_eq!(result.islands.len(), 2);
+        assert_eq!(result.islands[1].best.fitness(), 1000.0);
+        assert_eq!(result.best.fitness(), 1000.0);
+        assert_eq!(result.migrations, 2);
+    }
+
+    #[test]
+    fn test_stopped_island_receives_no_migrants() {
+        // mutation reaches the target within a few generations
+        let good = vec![Target { value: 495 }; 10];
+        let mutating = GenAlgConfig::builder()
+            .with_generations(20)
+            .with_mutation_rate(1.0)
+            .with_seed(5)
+            .build()
+            .unwrap();
+        let bad = vec![Target { value: 0 }; 10];
+        let islands = vec![
+            GenAlg::from_config(10, Some(&good), mutating)
+                .with_stop_condition(StopCondition::TargetFitness(1000.0)),
+            GenAlg::from_config(10, Some(&bad), config(4)),
+        ];
+
+        let mut model = IslandModel::new(islands)
+            .with_migration(Migration::default().with_interval(1).with_migrants(1))
+            .with_seed(1);
+        let result = model.run().unwrap();
+
+        let stopped = &result.islands[0];
+        assert!(matches!(
+            stopped.stop_reason,
+            StopCondition::TargetFitness(_)
+        ));
+        assert!((1..20).contains(&stopped.generations));
+        assert_eq!(result.islands[1].generations, 4);
+        assert!(
+            model.islands()[0]
+                .current_population()
+                .iter()
+                .all(|indiv| indiv.obj().value != 0)
+        );
+    }
+
+    #[test]
+    fn test_seeded_island_runs_are_identical() {
+        let run = |topology| {
+            let mut model = IslandModel::<Target>::from_config(4, 20, config(10)).with_migration(
+                Migration::default()
+                    .with_interval(3)
+                    .with_topology(topology)
+                    .with_selection(MigrantSelection::Random)
+                    .with_replacement(Replacement::Random),
+            );
+            let result = model.run().unwrap();
+            let populations: Vec<Vec<Target>> = model
+                .islands()
+                .iter()
+                .map(|island| {
+                    island
+                        .current_population()
+                        .iter()
+                        .map(|indiv| indiv.obj().clone())
+                        .collect()
+                })
+                .collect();
+            (result.migrations, populations)
+        };
+
+        for topology in [Topology::Ring, Topology::FullyConnected, Topology::Random] {
+            let (migrations, populations) = run(topology);
+            assert_eq!(migrations, 3);
+            assert_eq!(populations, run(topology).1);
+        }
+    }
+
+    #[test]
+    fn test_migrants_update_unique_genomes() {
+        let population: Vec<Target> = (0..10).map(|value| Target { value }).collect();
+        let mut gen_alg = GenAlg::from_config(10, Some(&population), config(1));
+        assert_eq!(gen_alg.unique_genomes(), 10);
+
+        let migrants = vec![FitnessIndiv::new(&Target { value: 500 }); 5];
+        gen_alg.receive_migrants(
+            migrants,
+            Replacement::Worst,
+            &mut ChaCha8Rng::seed_from_u64(0),
+        );
+
+        // the five copies of the migrant count as one genome
+        assert_eq!(gen_alg.unique_genomes(), 6);
+    }
+
+    #[test]
+    fn test_destinations() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let ring = Migration::default();
+        assert_eq!(ring.destinations(2, 3, &mut rng), vec![0]);
+
+        let full = Migration::default().with_topology(Topology::FullyConnected);
+        assert_eq!(full.destinations(1, 3, &mut rng), vec![0, 2]);
+
+        let random = Migration::default().with_topology(Topology::Random);
+        for _ in 0..20 {
+            let destinations = random.destinations(1, 3, &mut rng);
+            assert_eq!(destinations.len(), 1);
+            assert_ne!(destinations[0], 1);
+        }
+    }
+}
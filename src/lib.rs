@@ -1,2 +1,13 @@
+pub mod cache;
+#[cfg(feature = "serde")]
+pub mod checkpoint;
+pub mod config;
+pub mod error;
 pub mod genalg;
 pub mod individual;
+pub mod island;
+pub mod observer;
+pub mod pareto;
+pub mod selection;
+pub mod stats;
+pub mod termination;
@@ -0,0 +1,171 @@
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+
+use crate::genalg::FitnessIndiv;
+use crate::individual::genetic::Genetic;
+use crate::termination::StopCondition;
+
+/// Snapshot of a run passed to observers
+#[derive(Debug)]
+pub struct GenerationView<'a, T: Genetic + Clone + Send + Sync + PartialEq + Eq> {
+    /// Generations completed by the `GenAlg`, including previous runs
+    pub generation: usize,
+    /// Current population, sorted from the best individual
+    pub population: &'a [FitnessIndiv<T>],
+    /// Best individual found so far
+    pub best: &'a FitnessIndiv<T>,
+}
+
+impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> GenerationView<'_, T> {
+    /// Mean fitness of the population
+    pub fn mean_fitness(&self) -> f32 {
+        let total: f32 = self.population.iter().map(|indiv| indiv.fitness()).sum();
+        total / self.population.len().max(1) as f32
+    }
+}
+
+/// Hooks called by `GenAlg` while it runs.
+///
+/// Every hook but `on_end` can return `ControlFlow::Break(())` to stop the run after the
+/// current generation, the run then reports `StopCondition::Requested`.
+pub trait Observer<T: Genetic + Clone + Send + Sync + PartialEq + Eq>: Send + Sync {
+    /// Called once the initial population is evaluated, before the first generation
+    fn on_start(&mut self, _view: &GenerationView<T>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called once the children and mutants of a generation are evaluated and sorted,
+    /// before the best individual is updated
+    fn on_evaluation(&mut self, _view: &GenerationView<T>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called after every generation
+    fn on_generation(&mut self, _view: &GenerationView<T>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called when the run stops
+    fn on_end(&mut self, _view: &GenerationView<T>, _stop_reason: &StopCondition) {}
+}
+
+/// Prints the best and mean fitness to stdout
+#[derive(Debug, Clone)]
+pub struct ProgressPrinter {
+    interval: usize,
+}
+
+impl ProgressPrinter {
+    /// Creates a printer reporting every generation
+    pub fn new() -> Self {
+        ProgressPrinter { interval: 1 }
+    }
+
+    /// Only reports every `interval` generations
+    pub fn with_interval(mut self, interval: usize) -> Self {
+        self.interval = interval.max(1);
+        self
+    }
+}
+
+impl Default for ProgressPrinter {
+    fn default() -> Self {
+        ProgressPrinter::new()
+    }
+}
+
+impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> Observer<T> for ProgressPrinter {
+    fn on_start(&mut self, view: &GenerationView<T>) -> ControlFlow<()> {
+        println!(
+            "Start at generation {}: best {}, mean {}",
+            view.generation,
+            view.best.fitness(),
+            view.mean_fitness()
+        );
+        ControlFlow::Continue(())
+    }
+
+    fn on_generation(&mut self, view: &GenerationView<T>) -> ControlFlow<()> {
+        if view.generation.is_multiple_of(self.interval) {
+            println!(
+                "Generation {}: best {}, mean {}",
+                view.generation,
+                view.best.fitness(),
+                view.mean_fitness()
+            );
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn on_end(&mut self, view: &GenerationView<T>, stop_reason: &StopCondition) {
+        println!(
+            "Stopped at generation {} ({}): best {}",
+            view.generation,
+            stop_reason,
+            view.best.fitness()
+        );
+    }
+}
+
+/// Fitness of a population at one generation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenerationRecord {
+    pub generation: usize,
+    /// Fitness of the best individual found so far
+    pub best: f32,
+    pub mean: f32,
+    /// Fitness of the worst individual of the population
+    pub worst: f32,
+}
+
+/// Records the fitness of the population at the start and after every generation.
+///
+/// Clones share their records, so a clone can be kept to read them after the run.
+#[derive(Debug, Clone, Default)]
+pub struct StatsCollector {
+    records: Arc<Mutex<Vec<GenerationRecord>>>,
+}
+
+impl StatsCollector {
+    pub fn new() -> Self {
+        StatsCollector::default()
+    }
+
+    /// Returns the records collected so far
+    pub fn records(&self) -> Vec<GenerationRecord> {
+        self.records
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    fn record<T: Genetic + Clone + Send + Sync + PartialEq + Eq>(&self, view: &GenerationView<T>) {
+        let worst = view
+            .population
+            .iter()
+            .map(|indiv| indiv.fitness())
+            .fold(f32::INFINITY, f32::min);
+
+        self.records
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(GenerationRecord {
+                generation: view.generation,
+                best: view.best.fitness(),
+                mean: view.mean_fitness(),
+                worst,
+            });
+    }
+}
+
+impl<T: Genetic + Clone + Send + Sync + PartialEq + Eq> Observer<T> for StatsCollector {
+    fn on_start(&mut self, view: &GenerationView<T>) -> ControlFlow<()> {
+        self.record(view);
+        ControlFlow::Continue(())
+    }
+
+    fn on_generation(&mut self, view: &GenerationView<T>) -> ControlFlow<()> {
+        self.record(view);
+        ControlFlow::Continue(())
+    }
+}
@@ -0,0 +1,166 @@
+use std::cmp::Ordering;
+
+/// Returns true if `a` is at least as good as `b` in every objective and better in one.
+///
+/// All objectives are maximized, like `Genetic::fitness`.
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    let mut better = false;
+
+    for (x, y) in a.iter().zip(b) {
+        if x < y {
+            return false;
+        }
+        better |= x > y;
+    }
+
+    better
+}
+
+/// Splits the objective vectors into Pareto fronts, returning the indices of every front.
+///
+/// The first front holds the non-dominated vectors, every following front the vectors
+/// only dominated by earlier fronts.
+pub fn non_dominated_sort(objectives: &[Vec<f32>]) -> Vec<Vec<usize>> {
+    let len = objectives.len();
+    // indices dominated by every vector, and number of vectors dominating it
+    let mut dominated: Vec<Vec<usize>> = vec![Vec::new(); len];
+    let mut domination_count = vec![0; len];
+
+    for i in 0..len {
+        for j in i + 1..len {
+            if dominates(&objectives[i], &objectives[j]) {
+                dominated[i].push(j);
+                domination_count[j] += 1;
+            } else if dominates(&objectives[j], &objectives[i]) {
+                dominated[j].push(i);
+                domination_count[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..len).filter(|&i| domination_count[i] == 0).collect();
+
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &i in &front {
+            for &j in &dominated[i] {
+                domination_count[j] -= 1;
+                if domination_count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+
+        fronts.push(front);
+        next.sort_unstable();
+        front = next;
+    }
+
+    fronts
+}
+
+/// Crowding distance of every member of `front`, in the order of `front`.
+///
+/// Boundary vectors of every objective get an infinite distance, so they are always kept.
+pub fn crowding_distance(objectives: &[Vec<f32>], front: &[usize]) -> Vec<f32> {
+    let mut distance = vec![0.0; front.len()];
+    if front.len() <= 2 {
+        distance.fill(f32::INFINITY);
+        return distance;
+    }
+
+    let objective_count = objectives[front[0]].len();
+    let mut order: Vec<usize> = (0..front.len()).collect();
+    // values of one objective for every member of the front
+    let column = |objective: usize| -> Vec<f32> {
+        front.iter().map(|&i| objectives[i][objective]).collect()
+    };
+
+    for objective in 0..objective_count {
+        let values = column(objective);
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distance[first] = f32::INFINITY;
+        distance[last] = f32::INFINITY;
+
+        let range = values[last] - values[first];
+        if range <= 0.0 {
+            continue;
+        }
+
+        for window in order.windows(3) {
+            distance[window[1]] += (values[window[2]] - values[window[0]]) / range;
+        }
+    }
+
+    distance
+}
+
+/// Crowded comparison of NSGA-II, lower rank first and larger crowding distance on ties
+pub fn crowded_cmp(rank_a: usize, distance_a: f32, rank_b: usize, distance_b: f32) -> Ordering {
+    rank_a
+        .cmp(&rank_b)
+        .then_with(|| distance_b.total_cmp(&distance_a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dominates() {
+        assert!(dominates(&[2.0, 2.0], &[1.0, 2.0]));
+        assert!(!dominates(&[2.0, 2.0], &[2.0, 2.0]));
+        assert!(!dominates(&[3.0, 1.0], &[1.0, 3.0]));
+        assert!(!dominates(&[1.0, 2.0], &[2.0, 2.0]));
+    }
+
+    #[test]
+    fn test_non_dominated_sort() {
+        let objectives = vec![
+            vec![1.0, 5.0],
+            vec![5.0, 1.0],
+            vec![3.0, 3.0],
+            vec![2.0, 2.0],
+            vec![1.0, 1.0],
+            vec![0.0, 4.0],
+        ];
+
+        let fronts = non_dominated_sort(&objectives);
+
+        assert_eq!(fronts, vec![vec![0, 1, 2], vec![3, 5], vec![4]]);
+    }
+
+    #[test]
+    fn test_crowding_distance() {
+        let objectives = vec![
+            vec![0.0, 4.0],
+            vec![1.0, 3.0],
+            vec![3.0, 1.0],
+            vec![4.0, 0.0],
+        ];
+
+        let distance = crowding_distance(&objectives, &[0, 1, 2, 3]);
+
+        assert_eq!(distance[0], f32::INFINITY);
+        assert_eq!(distance[3], f32::INFINITY);
+        // (3 - 0) / 4 for both objectives
+        assert_eq!(distance[1], 1.5);
+        assert_eq!(distance[2], 1.5);
+
+        assert!(
+            crowding_distance(&objectives, &[1, 2])
+                .iter()
+                .all(|d| d.is_infinite())
+        );
+    }
+
+    #[test]
+    fn test_crowded_cmp() {
+        assert_eq!(crowded_cmp(0, 1.0, 1, 5.0), Ordering::Less);
+        assert_eq!(crowded_cmp(1, 5.0, 1, 1.0), Ordering::Less);
+        assert_eq!(crowded_cmp(1, f32::INFINITY, 1, 1.0), Ordering::Less);
+    }
+}
@@ -0,0 +1,405 @@
+use std::fmt::{self, Debug};
+
+use rand::{
+    Rng, RngCore,
+    distr::{Distribution, weighted::WeightedIndex},
+};
+
+/// Strategy choosing which individuals survive into the next generation and become parents.
+///
+/// Strategies work on the fitness of every individual, higher fitness is better.
+pub trait Selection: Debug + Send + Sync {
+    /// Selects `count` individuals and returns their indices in `fitness`.
+    ///
+    /// Depending on the strategy an individual can be selected more than once.
+    /// Returns `None` if there are no individuals to select from.
+    fn select(&self, fitness: &[f32], count: usize, rng: &mut dyn RngCore) -> Option<Vec<usize>>;
+}
+
+/// Invalid parameter of a selection strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionError {
+    /// Tournament of size 0
+    EmptyTournament,
+    /// Linear rank selection pressure outside of [1.0, 2.0]
+    PressureOutOfRange(f32),
+    /// Exponential rank selection base outside of (0.0, 1.0)
+    BaseOutOfRange(f32),
+}
+
+impl fmt::Display for SelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectionError::EmptyTournament => write!(f, "tournament size must be at least 1"),
+            SelectionError::PressureOutOfRange(pressure) => {
+                write!(
+                    f,
+                    "linear rank pressure must be in [1.0, 2.0], got {}",
+                    pressure
+                )
+            }
+            SelectionError::BaseOutOfRange(base) => {
+                write!(
+                    f,
+                    "exponential rank base must be in (0.0, 1.0), got {}",
+                    base
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SelectionError {}
+
+/// Indices of the population, from the best to the worst individual
+fn ranked(fitness: &[f32]) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..fitness.len()).collect();
+    indices.sort_by(|&a, &b| fitness[b].total_cmp(&fitness[a]));
+    indices
+}
+
+/// Draws `count` indices with probability proportional to `weights`.
+///
+/// Falls back to uniform choice if no weight is positive, returns `None` if there are no weights.
+fn weighted(weights: &[f32], count: usize, rng: &mut dyn RngCore) -> Option<Vec<usize>> {
+    if weights.is_empty() {
+        return None;
+    }
+
+    let selected = match WeightedIndex::new(weights.iter().map(|w| w.max(0.0))) {
+        Ok(dist) => (0..count).map(|_| dist.sample(rng)).collect(),
+        Err(_) => (0..count)
+            .map(|_| rng.random_range(0..weights.len()))
+            .collect(),
+    };
+    Some(selected)
+}
+
+/// Selects the best individuals. Every individual is selected at most once.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Truncation;
+
+impl Selection for Truncation {
+    fn select(&self, fitness: &[f32], count: usize, _rng: &mut dyn RngCore) -> Option<Vec<usize>> {
+        if fitness.is_empty() {
+            return None;
+        }
+
+        let mut indices = ranked(fitness);
+        indices.truncate(count);
+        Some(indices)
+    }
+}
+
+/// Selects the best of `size` randomly chosen individuals, once for every selected individual
+#[derive(Debug, Clone, Copy)]
+pub struct Tournament {
+    size: usize,
+}
+
+impl Tournament {
+    /// Fails if `size` is 0
+    pub fn new(size: usize) -> Result<Self, SelectionError> {
+        if size == 0 {
+            return Err(SelectionError::EmptyTournament);
+        }
+        Ok(Tournament { size })
+    }
+
+    /// Getter for tournament size
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Selection for Tournament {
+    fn select(&self, fitness: &[f32], count: usize, rng: &mut dyn RngCore) -> Option<Vec<usize>> {
+        if fitness.is_empty() {
+            return None;
+        }
+
+        (0..count)
+            .map(|_| {
+                (0..self.size)
+                    .map(|_| rng.random_range(0..fitness.len()))
+                    .max_by(|&a, &b| fitness[a].total_cmp(&fitness[b]))
+            })
+            .collect()
+    }
+}
+
+/// Fitness proportionate selection. Negative fitness counts as 0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Roulette;
+
+impl Selection for Roulette {
+    fn select(&self, fitness: &[f32], count: usize, rng: &mut dyn RngCore) -> Option<Vec<usize>> {
+        weighted(fitness, count, rng)
+    }
+}
+
+/// Selection with probability decreasing linearly with rank.
+///
+/// `pressure` in `[1.0, 2.0]` is the expected number of times the best individual is selected
+/// per selected individual, the worst one is selected `2.0 - pressure` times.
+#[derive(Debug, Clone, Copy)]
+pub struct LinearRank {
+    pressure: f32,
+}
+
+impl LinearRank {
+    /// Fails if `pressure` is not in `[1.0, 2.0]`
+    pub fn new(pressure: f32) -> Result<Self, SelectionError> {
+        if !(1.0..=2.0).contains(&pressure) {
+            return Err(SelectionError::PressureOutOfRange(pressure));
+        }
+        Ok(LinearRank { pressure })
+    }
+
+    /// Getter for selection pressure
+    pub fn pressure(&self) -> f32 {
+        self.pressure
+    }
+}
+
+impl Default for LinearRank {
+    fn default() -> Self {
+        LinearRank { pressure: 1.5 }
+    }
+}
+
+impl Selection for LinearRank {
+    fn select(&self, fitness: &[f32], count: usize, rng: &mut dyn RngCore) -> Option<Vec<usize>> {
+        let indices = ranked(fitness);
+        let last_rank = indices.len().saturating_sub(1).max(1) as f32;
+
+        let weights: Vec<f32> = (0..indices.len())
+            .map(|rank| {
+                let position = (last_rank - rank as f32) / last_rank;
+                2.0 - self.pressure + 2.0 * (self.pressure - 1.0) * position
+            })
+            .collect();
+
+        let selected = weighted(&weights, count, rng)?;
+        Some(selected.into_iter().map(|rank| indices[rank]).collect())
+    }
+}
+
+/// Selection with probability `base^rank`, so the best individual has rank 0.
+///
+/// Smaller `base` in `(0.0, 1.0)` means stronger preference for the best individuals.
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialRank {
+    base: f32,
+}
+
+impl ExponentialRank {
+    /// Fails if `base` is not in `(0.0, 1.0)`
+    pub fn new(base: f32) -> Result<Self, SelectionError> {
+        if !(base > 0.0 && base < 1.0) {
+            return Err(SelectionError::BaseOutOfRange(base));
+        }
+        Ok(ExponentialRank { base })
+    }
+
+    /// Getter for base of the rank weights
+    pub fn base(&self) -> f32 {
+        self.base
+    }
+}
+
+impl Default for ExponentialRank {
+    fn default() -> Self {
+        ExponentialRank { base: 0.9 }
+    }
+}
+
+impl Selection for ExponentialRank {
+    fn select(&self, fitness: &[f32], count: usize, rng: &mut dyn RngCore) -> Option<Vec<usize>> {
+        let indices = ranked(fitness);
+        let weights: Vec<f32> = (0..indices.len())
+            .map(|rank| self.base.powi(rank as i32))
+            .collect();
+
+        let selected = weighted(&weights, count, rng)?;
+        Some(selected.into_iter().map(|rank| indices[rank]).collect())
+    }
+}
+
+/// Fitness proportionate selection with evenly spaced pointers on a single spin of the wheel.
+///
+/// Unlike `Roulette`, every individual is selected close to its expected number of times.
+/// Negative fitness counts as 0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StochasticUniversalSampling;
+
+impl Selection for StochasticUniversalSampling {
+    fn select(&self, fitness: &[f32], count: usize, rng: &mut dyn RngCore) -> Option<Vec<usize>> {
+        let total: f32 = fitness.iter().map(|f| f.max(0.0)).sum();
+
+        if fitness.is_empty() || count == 0 || total <= 0.0 {
+            return weighted(fitness, count, rng);
+        }
+
+        let spacing = total / count as f32;
+        let start = rng.random::<f32>() * spacing;
+
+        let mut selected = Vec::with_capacity(count);
+        let mut index = 0;
+        let mut cumulative = fitness[0].max(0.0);
+
+        for i in 0..count {
+            let pointer = start + i as f32 * spacing;
+            while cumulative <= pointer && index + 1 < fitness.len() {
+                index += 1;
+                cumulative += fitness[index].max(0.0);
+            }
+            selected.push(index);
+        }
+
+        Some(selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    const FITNESS: [f32; 10] = [3.0, 9.0, 1.0, 7.0, 0.0, 5.0, 2.0, 8.0, 4.0, 6.0];
+
+    fn strategies() -> Vec<Box<dyn Selection>> {
+        vec![
+            Box::new(Truncation),
+            Box::new(Tournament::new(3).unwrap()),
+            Box::new(Roulette),
+            Box::new(LinearRank::default()),
+            Box::new(ExponentialRank::default()),
+            Box::new(StochasticUniversalSampling),
+        ]
+    }
+
+    fn mean_fitness(selected: &[usize]) -> f32 {
+        selected.iter().map(|&i| FITNESS[i]).sum::<f32>() / selected.len() as f32
+    }
+
+    #[test]
+    fn test_selection_count_and_range() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for strategy in strategies() {
+            let selected = strategy.select(&FITNESS, 5, &mut rng).unwrap();
+
+            assert_eq!(selected.len(), 5, "{:?}", strategy);
+            assert!(
+                selected.iter().all(|&i| i < FITNESS.len()),
+                "{:?}",
+                strategy
+            );
+        }
+    }
+
+    #[test]
+    fn test_selection_prefers_fitter() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let uniform_mean = FITNESS.iter().sum::<f32>() / FITNESS.len() as f32;
+
+        // Truncation cannot select more individuals than there are, it is tested separately
+        for strategy in strategies().into_iter().skip(1) {
+            let selected = strategy.select(&FITNESS, 5000, &mut rng).unwrap();
+
+            assert!(
+                mean_fitness(&selected) > uniform_mean + 0.5,
+                "{:?} does not prefer fitter individuals",
+                strategy
+            );
+        }
+    }
+
+    #[test]
+    fn test_truncation() {
+        let selected = Truncation
+            .select(&FITNESS, 3, &mut StdRng::seed_from_u64(0))
+            .unwrap();
+
+        assert_eq!(selected, vec![1, 7, 3]);
+    }
+
+    #[test]
+    fn test_tournament_of_whole_population() {
+        let mut rng = StdRng::seed_from_u64(0);
+        // Large tournaments almost always contain the best individual
+        let selected = Tournament::new(100)
+            .unwrap()
+            .select(&FITNESS, 10, &mut rng)
+            .unwrap();
+
+        assert!(selected.iter().all(|&i| i == 1));
+    }
+
+    #[test]
+    fn test_stochastic_universal_sampling() {
+        let selected = StochasticUniversalSampling
+            .select(&[1.0, 1.0, 2.0, 0.0], 4, &mut StdRng::seed_from_u64(0))
+            .unwrap();
+
+        assert_eq!(selected.iter().filter(|&&i| i == 0).count(), 1);
+        assert_eq!(selected.iter().filter(|&&i| i == 1).count(), 1);
+        assert_eq!(selected.iter().filter(|&&i| i == 2).count(), 2);
+    }
+
+    #[test]
+    fn test_zero_fitness_is_uniform() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for strategy in [
+            Box::new(Roulette) as Box<dyn Selection>,
+            Box::new(StochasticUniversalSampling),
+        ] {
+            let selected = strategy.select(&[0.0; 4], 400, &mut rng).unwrap();
+            assert!((0..4).all(|i| selected.contains(&i)), "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_rank_ignores_fitness_scale() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let squashed: Vec<f32> = FITNESS.iter().map(|f| f / 1000.0).collect();
+
+        let selected = ExponentialRank::new(0.5)
+            .unwrap()
+            .select(&squashed, 5000, &mut rng)
+            .unwrap();
+        let best_count = selected.iter().filter(|&&i| i == 1).count();
+
+        // Half of the selections are expected to be the best individual
+        assert!((2000..3000).contains(&best_count));
+    }
+
+    #[test]
+    fn test_empty_population() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for strategy in strategies() {
+            assert_eq!(strategy.select(&[], 3, &mut rng), None, "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert_eq!(
+            Tournament::new(0).unwrap_err(),
+            SelectionError::EmptyTournament
+        );
+        assert_eq!(
+            LinearRank::new(2.5).unwrap_err(),
+            SelectionError::PressureOutOfRange(2.5)
+        );
+        assert_eq!(
+            ExponentialRank::new(1.0).unwrap_err(),
+            SelectionError::BaseOutOfRange(1.0)
+        );
+        assert!(ExponentialRank::new(f32::NAN).is_err());
+    }
+}
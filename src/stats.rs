@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Statistics of the population after one generation
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GenerationStats {
+    /// Generations completed by the `GenAlg`, including previous runs
+    pub generation: usize,
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub mean_fitness: f32,
+    pub median_fitness: f32,
+    /// Population standard deviation of the fitness
+    pub stddev_fitness: f32,
+    /// Number of distinct genomes in the population
+    pub unique_genomes: usize,
+    /// Fitness evaluations done during the generation
+    pub evaluations: usize,
+    /// Fitness evaluations which failed during the generation, see `Genetic::try_fitness`
+    pub evaluation_errors: usize,
+    /// Fitness values taken from the fitness cache during the generation
+    pub cache_hits: usize,
+    /// Time spent evaluating fitness, summed over all threads
+    pub evaluation_time: Duration,
+    /// Time spent in selection, crossover and mutation, summed over all threads
+    pub operator_time: Duration,
+    /// Mean distance between two individuals, if the `GenAlg` measures genome distance
+    pub mean_distance: Option<f32>,
+}
+
+/// Minimum, maximum, mean, median and standard deviation of fitness values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FitnessSummary {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub median: f32,
+    pub stddev: f32,
+}
+
+impl FitnessSummary {
+    /// Summarizes the fitness values, all zero for an empty slice
+    pub fn new(fitness: &[f32]) -> Self {
+        if fitness.is_empty() {
+            return FitnessSummary {
+                min: 0.0,
+                max: 0.0,
+                mean: 0.0,
+                median: 0.0,
+                stddev: 0.0,
+            };
+        }
+
+        let mut sorted = fitness.to_vec();
+        sorted.sort_by(f32::total_cmp);
+
+        let len = sorted.len();
+        let median = if len.is_multiple_of(2) {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0
+        } else {
+            sorted[len / 2]
+        };
+
+        let mean = sorted.iter().sum::<f32>() / len as f32;
+        let variance = sorted.iter().map(|f| (f - mean).powi(2)).sum::<f32>() / len as f32;
+
+        FitnessSummary {
+            min: sorted[0],
+            max: sorted[len - 1],
+            mean,
+            median,
+            stddev: variance.sqrt(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fitness_summary() {
+        let summary = FitnessSummary::new(&[4.0, 1.0, 3.0, 2.0]);
+
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.max, 4.0);
+        assert_eq!(summary.mean, 2.5);
+        assert_eq!(summary.median, 2.5);
+        assert!((summary.stddev - 1.25_f32.sqrt()).abs() < 1e-6);
+
+        assert_eq!(FitnessSummary::new(&[3.0, 1.0, 2.0]).median, 2.0);
+        assert_eq!(FitnessSummary::new(&[]).max, 0.0);
+    }
+}
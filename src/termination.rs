@@ -0,0 +1,239 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Progress of a run, checked by stop conditions before every generation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunState {
+    /// Generations completed in this run
+    pub generation: usize,
+    /// Fitness of the best individual found so far
+    pub best_fitness: f32,
+    /// Generations since the best individual last improved
+    pub stagnant_generations: usize,
+    /// Time since the start of the run
+    pub elapsed: Duration,
+    /// Fitness evaluations in this run
+    pub evaluations: usize,
+    /// Fraction of distinct genomes in the current population (0.0 - 1.0).
+    ///
+    /// Only counted for conditions which may read it, NaN otherwise.
+    pub diversity: f32,
+}
+
+/// Condition ending a genetic algorithm run.
+///
+/// Conditions are combined with `or` and `and`, the run result reports the condition which fired.
+#[derive(Clone)]
+pub enum StopCondition {
+    /// Stop after the number of generations
+    Generations(usize),
+    /// Stop once the best fitness reaches the target
+    TargetFitness(f32),
+    /// Stop if the best individual did not improve for the number of generations
+    Stagnation(usize),
+    /// Stop once the run took longer than the duration
+    TimeLimit(Duration),
+    /// Stop once the run evaluated the fitness of at least the number of individuals
+    Evaluations(usize),
+    /// Stop once the fraction of distinct genomes drops below the threshold
+    DiversityBelow(f32),
+    /// Stop once the predicate returns true
+    Predicate(Arc<dyn Fn(&RunState) -> bool + Send + Sync>),
+    /// Stop once any of the conditions fires
+    Any(Vec<StopCondition>),
+    /// Stop once all of the conditions fire at the same time
+    All(Vec<StopCondition>),
+    /// Never fires on its own, reported when an observer asks the run to stop
+    Requested,
+}
+
+impl StopCondition {
+    /// Creates a condition from a user predicate
+    pub fn predicate(predicate: impl Fn(&RunState) -> bool + Send + Sync + 'static) -> Self {
+        StopCondition::Predicate(Arc::new(predicate))
+    }
+
+    /// Stops once either condition fires
+    pub fn or(self, other: StopCondition) -> Self {
+        match self {
+            StopCondition::Any(mut conditions) => {
+                conditions.push(other);
+                StopCondition::Any(conditions)
+            }
+            condition => StopCondition::Any(vec![condition, other]),
+        }
+    }
+
+    /// Stops once both conditions fire at the same time
+    pub fn and(self, other: StopCondition) -> Self {
+        match self {
+            StopCondition::All(mut conditions) => {
+                conditions.push(other);
+                StopCondition::All(conditions)
+            }
+            condition => StopCondition::All(vec![condition, other]),
+        }
+    }
+
+    /// Returns true if the condition may read the diversity of the run state
+    pub(crate) fn needs_diversity(&self) -> bool {
+        match self {
+            StopCondition::DiversityBelow(_) | StopCondition::Predicate(_) => true,
+            StopCondition::Any(conditions) | StopCondition::All(conditions) => {
+                conditions.iter().any(StopCondition::needs_diversity)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the condition which fired, if any.
+    ///
+    /// For `Any` this is the first child which fired, for `All` the whole condition.
+    pub fn check(&self, state: &RunState) -> Option<&StopCondition> {
+        let fired = match self {
+            StopCondition::Generations(generations) => state.generation >= *generations,
+            StopCondition::TargetFitness(target) => state.best_fitness >= *target,
+            StopCondition::Stagnation(generations) => state.stagnant_generations >= *generations,
+            StopCondition::TimeLimit(limit) => state.elapsed >= *limit,
+            StopCondition::Evaluations(evaluations) => state.evaluations >= *evaluations,
+            StopCondition::DiversityBelow(threshold) => state.diversity < *threshold,
+            StopCondition::Predicate(predicate) => predicate(state),
+            StopCondition::Any(conditions) => {
+                return conditions
+                    .iter()
+                    .find_map(|condition| condition.check(state));
+            }
+            StopCondition::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.check(state).is_some()),
+            StopCondition::Requested => false,
+        };
+
+        fired.then_some(self)
+    }
+}
+
+impl fmt::Debug for StopCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopCondition::Generations(n) => f.debug_tuple("Generations").field(n).finish(),
+            StopCondition::TargetFitness(t) => f.debug_tuple("TargetFitness").field(t).finish(),
+            StopCondition::Stagnation(n) => f.debug_tuple("Stagnation").field(n).finish(),
+            StopCondition::TimeLimit(d) => f.debug_tuple("TimeLimit").field(d).finish(),
+            StopCondition::Evaluations(n) => f.debug_tuple("Evaluations").field(n).finish(),
+            StopCondition::DiversityBelow(t) => f.debug_tuple("DiversityBelow").field(t).finish(),
+            StopCondition::Predicate(_) => write!(f, "Predicate"),
+            StopCondition::Any(c) => f.debug_tuple("Any").field(c).finish(),
+            StopCondition::All(c) => f.debug_tuple("All").field(c).finish(),
+            StopCondition::Requested => write!(f, "Requested"),
+        }
+    }
+}
+
+impl fmt::Display for StopCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, conditions: &[StopCondition], separator| {
+            for (i, condition) in conditions.iter().enumerate() {
+                if i > 0 {
+                    write!(f, "{}", separator)?;
+                }
+                write!(f, "{}", condition)?;
+            }
+            Ok(())
+        };
+
+        match self {
+            StopCondition::Generations(n) => write!(f, "reached {} generations", n),
+            StopCondition::TargetFitness(t) => write!(f, "reached target fitness {}", t),
+            StopCondition::Stagnation(n) => write!(f, "no improvement for {} generations", n),
+            StopCondition::TimeLimit(d) => write!(f, "time limit of {:?} exceeded", d),
+            StopCondition::Evaluations(n) => write!(f, "reached {} fitness evaluations", n),
+            StopCondition::DiversityBelow(t) => write!(f, "diversity dropped below {}", t),
+            StopCondition::Predicate(_) => write!(f, "user predicate"),
+            StopCondition::Any(c) => join(f, c, " or "),
+            StopCondition::All(c) => join(f, c, " and "),
+            StopCondition::Requested => write!(f, "stopped by an observer"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> RunState {
+        RunState {
+            generation: 10,
+            best_fitness: 50.0,
+            stagnant_generations: 3,
+            elapsed: Duration::from_secs(5),
+            evaluations: 1000,
+            diversity: 0.4,
+        }
+    }
+
+    #[test]
+    fn test_single_conditions() {
+        let state = state();
+
+        for (condition, fires) in [
+            (StopCondition::Generations(10), true),
+            (StopCondition::Generations(11), false),
+            (StopCondition::TargetFitness(50.0), true),
+            (StopCondition::TargetFitness(50.5), false),
+            (StopCondition::Stagnation(3), true),
+            (StopCondition::Stagnation(4), false),
+            (StopCondition::TimeLimit(Duration::from_secs(5)), true),
+            (StopCondition::TimeLimit(Duration::from_secs(6)), false),
+            (StopCondition::Evaluations(1000), true),
+            (StopCondition::Evaluations(1001), false),
+            (StopCondition::DiversityBelow(0.5), true),
+            (StopCondition::DiversityBelow(0.4), false),
+            (StopCondition::predicate(|s| s.generation == 10), true),
+            (StopCondition::predicate(|s| s.generation == 11), false),
+            (StopCondition::Requested, false),
+        ] {
+            assert_eq!(condition.check(&state).is_some(), fires, "{:?}", condition);
+        }
+    }
+
+    #[test]
+    fn test_composed_conditions() {
+        let state = state();
+
+        let any = StopCondition::Generations(100)
+            .or(StopCondition::Stagnation(3))
+            .or(StopCondition::TargetFitness(10.0));
+        assert!(matches!(
+            any.check(&state),
+            Some(StopCondition::Stagnation(3))
+        ));
+
+        let all = StopCondition::Stagnation(3).and(StopCondition::Generations(100));
+        assert!(all.check(&state).is_none());
+
+        let all = StopCondition::Stagnation(3).and(StopCondition::TargetFitness(10.0));
+        assert!(matches!(all.check(&state), Some(StopCondition::All(_))));
+        assert_eq!(
+            all.to_string(),
+            "no improvement for 3 generations and reached target fitness 10"
+        );
+    }
+
+    #[test]
+    fn test_needs_diversity() {
+        assert!(!StopCondition::Generations(10).needs_diversity());
+        assert!(
+            !StopCondition::Stagnation(3)
+                .or(StopCondition::TargetFitness(10.0))
+                .needs_diversity()
+        );
+        assert!(
+            StopCondition::Generations(10)
+                .or(StopCondition::Stagnation(3).and(StopCondition::DiversityBelow(0.5)))
+                .needs_diversity()
+        );
+        assert!(StopCondition::predicate(|s| s.diversity < 0.1).needs_diversity());
+    }
+}
@@ -1,6 +1,12 @@
+use std::sync::Arc;
 use std::time::Instant;
 
-use gen_alg::{genalg::GenAlg, individual::dlx::Individual};
+use gen_alg::individual::dlx::backend::ScriptedBackend;
+use gen_alg::individual::dlx::emu::EmulatorResult;
+use gen_alg::individual::dlx::worker::WorkerCommand;
+use gen_alg::individual::dlx::{EmulatorBackend, WorkerPool};
+use gen_alg::individual::genetic::Genetic;
+use gen_alg::{config::GenAlgConfig, genalg::GenAlg, individual::dlx::Individual};
 const SOI_ALG_START: &str = "ADDI R0, 0x00000010, R12\nADDI R0, 0x00000020, R11\nAND R1, R0, R1\nMULI R12, 0x00000004, R12\nAND R4, R0, R4\nMULI R11, 0x00000004, R11\nSUBI R12, 0x00000004, R13\nLDW R7, 0x00000200(R1)\nADD R4, R0, R5\nSUBI R13, 0x00000004, R17\nl1: AND R2, R0, R2\nAND R3, R0, R3\nSTW R7, 0x00000280(R4)\nLDW R9, 0x00000280(R5)\nLDW R10, 0x000002C0(R2)\nl2: SUB R5, R17, R14\nADD R3, R9, R3\nADDI R2, 0x00000004, R2\nBRLE R14, h1\nSUB R2, R12, R15\nADDI R5, 0x00000004, R5\nAND R5, R0, R5\nh1: MUL R3, R10, R3\nBRNZ R15, l2\nLDW R9, 0x00000280(R5)\nLDW R10, 0x000002C0(R2)\nSTW R3, 0x00000300(R1)\nSUBI R4, 0x00000004, R4\nADDI R1, 0x00000004, R1\nNOP\nBRGE R4, h2\nSUB R1, R11, R15\nNOP\nADD R13, R0, R4\nh2: NOP\nBRNZ R15, l1\nLDW R7, 0x00000200(R1)\nADD R4, R0, R5";
 
 #[test]
@@ -12,10 +18,20 @@ fn test_dlx_gen_alg() {
 
     let mut gen_alg = GenAlg::<Individual>::new(
         pop_size,
-        Some(&vec![Individual::new(SOI_ALG_START); pop_size]),
+        Some(&vec![Individual::new(SOI_ALG_START).unwrap(); pop_size]),
     );
 
-    let best = gen_alg.run_genetic_algorithm(gen_num, 0.5, 0.5, 5).unwrap();
+    gen_alg.set_config(
+        GenAlgConfig::builder()
+            .with_generations(gen_num)
+            .with_selection_rate(0.5)
+            .with_mutation_rate(0.5)
+            .with_elite_count(5)
+            .build()
+            .unwrap(),
+    );
+
+    let best = gen_alg.run_genetic_algorithm().unwrap().best;
 
     println!("{}", SOI_ALG_START);
     println!("{}\n Fitness: {}", best.obj(), best.fitness());
@@ -26,6 +42,65 @@ fn test_dlx_gen_alg() {
     println!("Time elapsed: {:?}", duration);
 }
 
+#[test]
+fn test_dlx_gen_alg_scripted_backend() {
+    let failed = EmulatorResult {
+        success: false,
+        cycle_count: 0,
+        unknown: true,
+        memory: None,
+    };
+    let scripted = Arc::new(ScriptedBackend::new(failed));
+    let backend: Arc<dyn EmulatorBackend> = scripted.clone();
+
+    let config = GenAlgConfig::builder()
+        .with_generations(5)
+        .with_elite_count(1)
+        .with_seed(3)
+        .build()
+        .unwrap();
+    let mut gen_alg = GenAlg::from_generator(10, config, |rng| {
+        Individual::generate(rng).with_backend(backend.clone())
+    });
+
+    let best = gen_alg.run_genetic_algorithm().unwrap().best;
+
+    // Native runs of the generated programs succeed, the scripted result never does
+    assert_eq!(best.fitness(), 0.0);
+    assert!(scripted.calls() > 0);
+    assert!(Arc::ptr_eq(best.obj().backend(), &backend));
+}
+
+#[test]
+fn test_dlx_gen_alg_counts_evaluation_errors() {
+    // Workers which can not be started fail every evaluation
+    let backend: Arc<dyn EmulatorBackend> = Arc::new(WorkerPool::with_size(
+        WorkerCommand::new("/nonexistent/emu_worker"),
+        1,
+    ));
+    let config = GenAlgConfig::builder()
+        .with_generations(3)
+        .with_seed(1)
+        .build()
+        .unwrap();
+    let mut gen_alg = GenAlg::from_generator(6, config, |rng| {
+        Individual::generate(rng).with_backend(backend.clone())
+    });
+    // Every individual of the initial population is evaluated
+    assert_eq!(gen_alg.evaluation_errors(), 6);
+
+    let result = gen_alg.run_genetic_algorithm().unwrap();
+
+    assert_eq!(result.best.fitness(), 0.0);
+    assert_eq!(gen_alg.evaluation_errors(), gen_alg.evaluations());
+    assert!(
+        gen_alg
+            .generation_stats()
+            .iter()
+            .all(|stats| stats.evaluation_errors == stats.evaluations)
+    );
+}
+
 #[test]
 #[ignore]
 fn test_dlx_gen_alg_long() {
@@ -36,10 +111,20 @@ fn test_dlx_gen_alg_long() {
 
     let mut gen_alg = GenAlg::<Individual>::new(
         pop_size,
-        Some(&vec![Individual::new(SOI_ALG_LONG); pop_size]),
+        Some(&vec![Individual::new(SOI_ALG_LONG).unwrap(); pop_size]),
+    );
+
+    gen_alg.set_config(
+        GenAlgConfig::builder()
+            .with_generations(gen_num)
+            .with_selection_rate(0.5)
+            .with_mutation_rate(0.5)
+            .with_elite_count(3)
+            .build()
+            .unwrap(),
     );
 
-    let best = gen_alg.run_genetic_algorithm(gen_num, 0.5, 0.5, 3).unwrap();
+    let best = gen_alg.run_genetic_algorithm().unwrap().best;
 
     println!("{}", SOI_ALG_LONG);
     println!("{}\n Fitness: {}", best.obj(), best.fitness());
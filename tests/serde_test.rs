@@ -0,0 +1,167 @@
+#![cfg(feature = "serde")]
+
+use std::fmt::Debug;
+use std::fs;
+use std::sync::Arc;
+
+use gen_alg::{
+    config::GenAlgConfig,
+    genalg::{FitnessIndiv, GenAlg},
+    individual::dlx::{
+        EmulatorBackend, Individual, Instruction, Opcode, Register, Task,
+        backend::NativeBackend,
+        emu::EmulatorResult,
+        pipeline::{Forwarding, PipelineConfig},
+    },
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+const PROGRAM: &str = "ADDI R0, 0x00000010, R12\nl1: LDW R7, 0x00000200(R1)\nSUBI R12, 0x00000001, R12\nBRNZ R12, l1\nSTW R7, 0x00000300(R0)";
+
+/// Serializes `value` as JSON and with bincode, returning both deserialized copies
+fn round_trip<T: Serialize + DeserializeOwned + Debug>(value: &T) -> (T, T) {
+    let json = serde_json::to_string(value).unwrap();
+    let binary = bincode::serialize(value).unwrap();
+
+    (
+        serde_json::from_str(&json).unwrap(),
+        bincode::deserialize(&binary).unwrap(),
+    )
+}
+
+#[test]
+fn test_opcode_and_register_round_trip() {
+    for opcode in [Opcode::ADD, Opcode::LDW, Opcode::BRNZ, Opcode::NOP] {
+        assert_eq!(round_trip(&opcode), (opcode, opcode));
+    }
+
+    for register in [Register::R0, Register::R12, Register::R31] {
+        assert_eq!(round_trip(&register), (register.clone(), register));
+    }
+}
+
+#[test]
+fn test_instruction_round_trip() {
+    for line in PROGRAM.lines() {
+        let line = line.split_once(": ").map_or(line, |(_, instr)| instr);
+        let instruction = Instruction::new(line).unwrap();
+
+        let (json, binary) = round_trip(&instruction);
+        assert_eq!(json, instruction);
+        assert_eq!(binary, instruction);
+    }
+}
+
+#[test]
+fn test_individual_round_trip() {
+    let indiv = Individual::new(PROGRAM).unwrap();
+
+    let (json, binary) = round_trip(&indiv);
+
+    // equality covers the label table, which the assembly text only shows inline
+    assert_eq!(json, indiv);
+    assert_eq!(binary, indiv);
+    assert_eq!(binary.get_label_position("l1"), Some(1));
+    assert_eq!(binary.to_string(), indiv.to_string());
+
+    let binary_len = bincode::serialize(&indiv).unwrap().len();
+    assert!(binary_len < serde_json::to_vec(&indiv).unwrap().len());
+}
+
+#[test]
+fn test_fitness_indiv_round_trip() {
+    let indiv = FitnessIndiv::new(&Individual::new(PROGRAM).unwrap());
+
+    let (json, binary) = round_trip(&indiv);
+
+    for restored in [json, binary] {
+        assert_eq!(restored.obj(), indiv.obj());
+        assert_eq!(restored.fitness(), indiv.fitness());
+    }
+}
+
+#[test]
+fn test_emulator_result_round_trip() {
+    let results = [
+        EmulatorResult {
+            success: true,
+            cycle_count: 1234,
+            unknown: false,
+            memory: Some(vec![0, 7, u32::MAX]),
+        },
+        EmulatorResult {
+            success: false,
+            cycle_count: 0,
+            unknown: true,
+            memory: None,
+        },
+    ];
+
+    for result in results {
+        let (json, binary) = round_trip(&result);
+
+        for restored in [json, binary] {
+            assert_eq!(restored.success, result.success);
+            assert_eq!(restored.cycle_count, result.cycle_count);
+            assert_eq!(restored.unknown, result.unknown);
+            assert_eq!(restored.memory, result.memory);
+        }
+    }
+}
+
+#[test]
+fn test_dlx_resume_on_custom_task() {
+    let task = Arc::new(
+        Task::new("double")
+            .with_memory(0x0, &[21])
+            .with_expected(0x4, &[42])
+            .with_cycle_budget(100),
+    );
+    let backend: Arc<dyn EmulatorBackend> =
+        Arc::new(NativeBackend::with_pipeline(PipelineConfig {
+            forwarding: Forwarding::full(),
+            ..PipelineConfig::default()
+        }));
+    let bind = |indiv: Individual| indiv.with_task(task.clone()).with_backend(backend.clone());
+
+    let programs: Vec<Individual> = [
+        "LDW R1, 0x00000000(R0)\nADD R1, R1, R2\nSTW R2, 0x00000004(R0)",
+        "LDW R1, 0x00000000(R0)\nSTW R1, 0x00000004(R0)",
+    ]
+    .iter()
+    .flat_map(|program| vec![bind(Individual::new(program).unwrap()); 5])
+    .collect();
+    let config = GenAlgConfig::builder()
+        .with_generations(10)
+        .with_seed(4)
+        .build()
+        .unwrap();
+    let path = std::env::temp_dir().join(format!("gen-alg-dlx-{}.checkpoint", std::process::id()));
+
+    let mut uninterrupted = GenAlg::from_config(10, Some(&programs), config.clone());
+    let expected = uninterrupted.run_genetic_algorithm().unwrap();
+
+    let mut interrupted = GenAlg::from_config(10, Some(&programs), config)
+        .with_checkpoints(&path, 5)
+        .unwrap();
+    for summary in interrupted.generations().take(7) {
+        summary.unwrap();
+    }
+
+    let mut resumed = GenAlg::<Individual>::resume(&path, bind).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(resumed.current_population().iter().all(|indiv| {
+        Arc::ptr_eq(indiv.obj().task(), &task) && Arc::ptr_eq(indiv.obj().backend(), &backend)
+    }));
+
+    let result = resumed.run_genetic_algorithm().unwrap();
+    assert_eq!(result.best.obj(), expected.best.obj());
+    assert_eq!(result.best.fitness(), expected.best.fitness());
+    assert!(
+        resumed
+            .current_population()
+            .iter()
+            .zip(uninterrupted.current_population())
+            .all(|(a, b)| a.obj() == b.obj() && a.fitness() == b.fitness())
+    );
+}
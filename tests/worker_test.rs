@@ -0,0 +1,223 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use gen_alg::config::GenAlgConfig;
+use gen_alg::error::EmulatorError;
+use gen_alg::genalg::GenAlg;
+use gen_alg::individual::dlx::worker::WorkerCommand;
+use gen_alg::individual::dlx::{EmulatorBackend, Individual, Task, WorkerPool, emu};
+use gen_alg::individual::genetic::Genetic;
+use rayon::prelude::*;
+
+const SOI_CODE: &str = include_str!("../examples/short_code.txt");
+
+fn stub_worker() -> WorkerCommand {
+    WorkerCommand::new(env!("CARGO_BIN_EXE_emu_worker"))
+}
+
+/// Stand-in for `interface.py` which reports the number of program lines as the cycle count
+/// and fails programs containing a branch or going over the timeout
+const FAKE_INTERFACE: &str = r#"
+import argparse
+
+parser = argparse.ArgumentParser()
+parser.add_argument("--instr")
+parser.add_argument("--timeout", type=int)
+args = parser.parse_args()
+
+if "BRZ" in args.instr or len(args.instr.splitlines()) > args.timeout:
+    print("Failed")
+else:
+    print("Completed")
+    print(len(args.instr.splitlines()))
+    print("False")
+    print("00000001 00000002")
+"#;
+
+/// Directory removed when dropped, so failed tests do not leave it behind
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn test_worker_pool_matches_native() {
+    let pool = WorkerPool::with_size(stub_worker(), 2);
+    let indiv = Individual::new(SOI_CODE).unwrap();
+
+    let worker_result = pool.run(&indiv, &emu::reference_memory()).unwrap();
+    let native_result = emu::run_native_emulator(&indiv);
+
+    assert!(worker_result.success);
+    assert_eq!(worker_result.cycle_count, native_result.cycle_count);
+    assert_eq!(worker_result.unknown, native_result.unknown);
+    assert_eq!(worker_result.memory, native_result.memory);
+    assert_eq!(pool.restarts(), 0);
+}
+
+#[test]
+fn test_worker_pool_failed_program() {
+    let pool = WorkerPool::with_size(stub_worker(), 1);
+    let result = pool.run(
+        &Individual::new("l1: NOP\nBRZ R0, l1").unwrap(),
+        &emu::reference_memory(),
+    );
+
+    assert!(!result.unwrap().success);
+    assert_eq!(pool.restarts(), 0);
+}
+
+#[test]
+fn test_worker_pool_cycle_budget() {
+    let pool = WorkerPool::with_size(stub_worker(), 1);
+    let with_budget = |budget| {
+        Individual::new(SOI_CODE).unwrap().with_task(Arc::new(
+            Task::clone(&Task::soi()).with_cycle_budget(budget),
+        ))
+    };
+
+    let result = pool
+        .run(&with_budget(100), &emu::reference_memory())
+        .unwrap();
+    assert!(!result.success);
+
+    let cycles = emu::run_native_emulator(&with_budget(20000)).cycle_count;
+    let result = pool
+        .run(&with_budget(cycles), &emu::reference_memory())
+        .unwrap();
+    assert!(result.success);
+    assert_eq!(result.cycle_count, cycles);
+}
+
+#[test]
+fn test_worker_pool_parallel() {
+    let pool = WorkerPool::new(stub_worker());
+    let indivs = vec![Individual::new(SOI_CODE).unwrap(); 16];
+    let expected = emu::run_native_emulator(&indivs[0]).cycle_count;
+
+    let results: Vec<_> = indivs
+        .par_iter()
+        .map(|indiv| pool.run(indiv, &emu::reference_memory()).unwrap())
+        .collect();
+
+    assert_eq!(pool.size(), rayon::current_num_threads());
+    assert!(
+        results
+            .iter()
+            .all(|result| result.success && result.cycle_count == expected)
+    );
+}
+
+#[test]
+fn test_worker_pool_restarts_crashed_worker() {
+    let pool = WorkerPool::with_size(stub_worker().arg("--crash-after").arg("1"), 1);
+    let indiv = Individual::new(SOI_CODE).unwrap();
+
+    for _ in 0..3 {
+        assert!(pool.run(&indiv, &emu::reference_memory()).unwrap().success);
+    }
+    assert_eq!(pool.restarts(), 2);
+}
+
+#[test]
+fn test_worker_pool_timeout() {
+    let pool = WorkerPool::with_size(stub_worker().arg("--delay-ms").arg("2000"), 1)
+        .with_timeout(Duration::from_millis(100));
+
+    let result = pool.run(
+        &Individual::new(SOI_CODE).unwrap(),
+        &emu::reference_memory(),
+    );
+
+    assert!(matches!(result, Err(EmulatorError::Timeout)));
+    assert_eq!(pool.restarts(), 1);
+}
+
+#[test]
+fn test_worker_pool_fitness() {
+    let pool: Arc<dyn EmulatorBackend> = Arc::new(WorkerPool::with_size(stub_worker(), 2));
+    let indiv = Individual::new(SOI_CODE).unwrap().with_backend(pool);
+
+    assert_eq!(
+        indiv.fitness(),
+        Individual::new(SOI_CODE).unwrap().fitness(),
+        "worker pool and native backend should agree"
+    );
+
+    let mut gen_alg = GenAlg::<Individual>::new(4, Some(&vec![indiv; 4]));
+    gen_alg.set_config(
+        GenAlgConfig::builder()
+            .with_generations(2)
+            .with_selection_rate(0.5)
+            .with_mutation_rate(0.5)
+            .with_elite_count(1)
+            .build()
+            .unwrap(),
+    );
+    assert!(gen_alg.run_genetic_algorithm().is_ok());
+}
+
+#[test]
+fn test_python_worker_round_trip() {
+    let emulator_dir = TempDir::new("gen-alg-emulator");
+    fs::write(emulator_dir.0.join("interface.py"), FAKE_INTERFACE).unwrap();
+
+    let command = WorkerCommand::python()
+        .arg("--emulator-dir")
+        .arg(emulator_dir.0.to_str().unwrap());
+    let pool = WorkerPool::with_size(command, 1);
+
+    // Both requests go to the same, still running interpreter
+    let program = Individual::new("NOP\nADD R1, R2, R3").unwrap();
+    for _ in 0..2 {
+        let result = pool.run(&program, &[]).unwrap();
+        assert!(result.success);
+        assert_eq!(result.cycle_count, program.to_string().lines().count());
+        assert_eq!(result.memory, Some(vec![1, 2]));
+    }
+
+    let result = pool
+        .run(&Individual::new("l1: NOP\nBRZ R0, l1").unwrap(), &[])
+        .unwrap();
+    assert!(!result.success);
+
+    // The cycle budget of the task is the timeout of the emulator
+    let over_budget = program
+        .clone()
+        .with_task(Arc::new(Task::new("short").with_cycle_budget(1)));
+    assert!(!pool.run(&over_budget, &[]).unwrap().success);
+
+    // The reference image is accepted, other images are refused instead of ignored
+    let memory = emu::reference_memory();
+    assert!(pool.run(&program, &memory).unwrap().success);
+    let result = pool.run(&program, &memory[..64]);
+    assert!(matches!(result, Err(EmulatorError::Failed(_))));
+    assert_eq!(pool.restarts(), 0);
+}
+
+#[test]
+#[ignore = "requires python3 and the src/emulator submodule"]
+fn test_python_worker_matches_native() {
+    let pool = WorkerPool::with_size(WorkerCommand::python(), 1);
+    let indiv = Individual::new(SOI_CODE).unwrap();
+
+    let worker_result = pool.run(&indiv, &emu::reference_memory()).unwrap();
+    let native_result = emu::run_native_emulator(&indiv);
+
+    assert!(worker_result.success);
+    assert_eq!(worker_result.cycle_count, native_result.cycle_count);
+    assert_eq!(worker_result.memory, native_result.memory);
+}